@@ -1,17 +1,35 @@
 // TODO: Should have special module on non-wasm platforms for file system access?
 
+use kot::SourceFile;
+use std::io::IsTerminal;
+
 fn main() -> anyhow::Result<()> {
-    println!("Hello, world!");
+    // Runs the script at the first argument, or a small example without one.
+    let file = match std::env::args().nth(1) {
+        Some(path) => {
+            let text = std::fs::read_to_string(&path)?;
+            SourceFile::new(path, text)
+        }
+        None => SourceFile::new("<example>", "1 +-1000000"),
+    };
 
-    // let string = std::fs::read_to_string("./test/iter_1/large.tmp")?;
+    match run(file.text()) {
+        Ok(result) => {
+            println!("Got final value: {result:?}");
+            Ok(())
+        }
+        Err(err) => {
+            let color = std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+            eprint!("{}", kot::diagnostics::render_file(&file, &err, color));
+            std::process::exit(1);
+        }
+    }
+}
 
-    let lex = kot::lex("1 +-1000000")?;
+fn run(source: &str) -> anyhow::Result<Option<kot::data::Typing>> {
+    let lex = kot::lex(source)?;
     let parse = kot::parse(lex)?;
 
     let mut int = kot::Interpreter::new(parse);
-    let result = int.run()?;
-
-    println!("Got final value: {result:?}");
-
-    Ok(())
+    Ok(int.run()?)
 }
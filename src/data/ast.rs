@@ -1,4 +1,5 @@
-use crate::Pos;
+use crate::{Pos, Span};
+use std::rc::Rc;
 
 // TODO: String with size limited to u16?
 pub type Ident = String;
@@ -6,15 +7,17 @@ pub type Ident = String;
 type Bst = Box<PosAst>;
 type Vst = Vec<PosAst>;
 
+/// The span covers all of the source text of the node, pos is where errors point.
 #[derive(Debug)]
 pub struct PosAst {
     pub ast: Ast,
     pub pos: Pos,
+    pub span: Span,
 }
 impl PosAst {
     #[must_use]
-    pub const fn new(ast: Ast, pos: Pos) -> Self {
-        Self { ast, pos }
+    pub const fn new(ast: Ast, pos: Pos, span: Span) -> Self {
+        Self { ast, pos, span }
     }
 }
 impl std::fmt::Display for PosAst {
@@ -25,41 +28,154 @@ impl std::fmt::Display for PosAst {
 
 #[derive(Debug)]
 pub enum Ast {
-    // TODO: Should use Vst and Maybe should just be a block.
-    Root(Bst),
+    /// Statements of the script, run in the global frame.
+    Root(Vst),
+    /// `{ ... }` runs in its own frame, the value is the last statement if it has no `;`.
     Block(Vst),
+    /// `expr;`, drops the value.
+    Statement(Bst),
+    /// `if cond { ... } else ...`, the else is a Block or another If.
+    If(Bst, Bst, Option<Bst>),
+    /// `guard cond, let x = expr else { ... }`, declarations stay in the enclosing scope.
+    /// The else block always diverges.
+    Guard(Vst, Bst),
+    /// `ret` with an optional value.
+    Return(Option<Bst>),
+    /// `'label: while cond { ... }`, the body is a Block so every iteration has its own frame.
+    While(Option<Ident>, Bst, Bst),
+    /// `break 'label value`, both are optional.
+    Break(Option<Ident>, Option<Bst>),
+    /// `continue 'label`, the label is optional.
+    Continue(Option<Ident>),
+    /// `'label: for id in iter { ... }`, id is declared in a new frame for every value.
+    For(Option<Ident>, Ident, Bst, Bst),
+    /// `start..<end by step`, inclusive for `..=`, the step is optional.
+    Range(bool, Bst, Bst, Option<Bst>),
+    /// `fn name(param: type) -> type { ... }`, shared with the values that call it.
+    Function(Rc<Function>),
+    /// `name(args)`
+    Call(Ident, Vst),
 
     UnaryOp(UnaryOperation, Bst),
     BinOp(BinaryOperation, Bst, Bst),
 
-    // TODO
+    /// let, const or var, with an optional type. See docs/VARIABLES.md.
+    Declaration(DeclarationKind, Ident, Option<RawTyping>, Bst),
+    /// Compound assignments like `x += 1` become `x = x + 1`.
     Assignment(Ident, Bst),
-    // TODO: Should this just be a Op and a Assignment, probably.
-    #[deprecated]
-    AssignmentOp(Ident, AssignmentOperation, Bst),
 
     Value(Typing),
 }
 impl std::fmt::Display for Ast {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Root(a) => write!(f, "FakeGlobal... {a}"),
+            Self::Root(stmts) => write!(f, "Root {{ {} }}", join(stmts)),
+            Self::Block(stmts) => write!(f, "Block {{ {} }}", join(stmts)),
+            Self::Statement(a) => write!(f, "Statement {{ {a} }}"),
+            Self::If(cond, then, Some(otherwise)) => {
+                write!(f, "If {{ {cond}, {then}, {otherwise} }}")
+            }
+            Self::If(cond, then, None) => write!(f, "If {{ {cond}, {then} }}"),
+            Self::Guard(conds, otherwise) => {
+                write!(f, "Guard {{ {}, {otherwise} }}", join(conds))
+            }
+            Self::Return(Some(a)) => write!(f, "Return {{ {a} }}"),
+            Self::Return(None) => write!(f, "Return"),
+            Self::While(Some(label), cond, body) => {
+                write!(f, "While '{label} {{ {cond}, {body} }}")
+            }
+            Self::While(None, cond, body) => write!(f, "While {{ {cond}, {body} }}"),
+            Self::Break(label, val) => {
+                write!(f, "Break")?;
+                if let Some(label) = label {
+                    write!(f, " '{label}")?;
+                }
+                match val {
+                    Some(val) => write!(f, " {{ {val} }}"),
+                    None => Ok(()),
+                }
+            }
+            Self::Continue(Some(label)) => write!(f, "Continue '{label}"),
+            Self::Continue(None) => write!(f, "Continue"),
+            Self::For(label, id, iter, body) => {
+                write!(f, "For")?;
+                if let Some(label) = label {
+                    write!(f, " '{label}")?;
+                }
+                write!(f, " {id} {{ {iter}, {body} }}")
+            }
+            Self::Range(inclusive, start, end, step) => {
+                write!(f, "Range {{ {start}, {end}")?;
+                if let Some(step) = step {
+                    write!(f, ", {step}")?;
+                }
+                let op = if *inclusive { "..=" } else { "..<" };
+                write!(f, " }} {op}")
+            }
+            Self::Function(function) => write!(f, "{function} {{ {} }}", function.body),
+            Self::Call(id, args) => write!(f, "Call {id} {{ {} }}", join(args)),
             Self::UnaryOp(op, a) => write!(f, "{op:?} {{ {a} }}"),
             Self::BinOp(op, a1, a2) => write!(f, "{op:?} {{ {a1}, {a2} }}"),
+            Self::Declaration(kind, id, Some(ty), a) => write!(f, "{kind} {id}: {ty} {{ {a} }}"),
+            Self::Declaration(kind, id, None, a) => write!(f, "{kind} {id} {{ {a} }}"),
+            Self::Assignment(id, a) => write!(f, "Assignment {id} {{ {a} }}"),
             Self::Value(val) => write!(f, "{val:?}"),
-            _ => panic!(),
         }
     }
 }
 
-#[derive(Debug)]
+fn join(stmts: &[PosAst]) -> String {
+    stmts
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DeclarationKind {
+    /// Immutable, and the value must be known without running the script.
+    Const,
+    /// Immutable.
+    Let,
+    /// Mutable.
+    Var,
+}
+impl DeclarationKind {
+    #[must_use]
+    pub const fn is_mutable(self) -> bool {
+        matches!(self, Self::Var)
+    }
+}
+impl std::fmt::Display for DeclarationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            Self::Const => "const",
+            Self::Let => "let",
+            Self::Var => "var",
+        };
+        write!(f, "{kind}")
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum UnaryOperation {
     Negate,
     BooleanNot,
     BitwiseNot,
 }
+impl std::fmt::Display for UnaryOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            Self::Negate => "-",
+            Self::BooleanNot => "!",
+            Self::BitwiseNot => "~",
+        };
+        write!(f, "{op}")
+    }
+}
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum BinaryOperation {
     Multiply,
     Divide,
@@ -84,15 +200,107 @@ pub enum BinaryOperation {
     BitwiseXor,
     BitwiseOr,
 }
+impl std::fmt::Display for BinaryOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            Self::Multiply => "*",
+            Self::Divide => "/",
+            Self::Modulus => "%",
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::BooleanAnd => "&&",
+            Self::BooleanXor => "^^",
+            Self::BooleanOr => "||",
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+            Self::Less => "<",
+            Self::LessEqual => "<=",
+            Self::Greater => ">",
+            Self::GreaterEqual => ">=",
+            Self::BitwiseShiftLeft => "<<",
+            Self::BitwiseShiftRight => ">>",
+            Self::BitwiseAnd => "&",
+            Self::BitwiseXor => "^",
+            Self::BitwiseOr => "|",
+        };
+        write!(f, "{op}")
+    }
+}
 
-#[derive(Debug)]
-#[deprecated]
-pub enum AssignmentOperation {}
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RawTyping {
+    Int64,
+    UInt64,
+    Float64,
 
-#[derive(Debug)]
-pub enum RawTyping {}
+    UInt8,
+    Boolean,
+
+    Character,
+    String,
+
+    Range,
+    Function,
+
+    Ident,
+}
+impl RawTyping {
+    /// Name used by number literal suffixes, if the type has one.
+    #[must_use]
+    pub fn from_suffix(suffix: &str) -> Option<Self> {
+        match suffix {
+            "i64" => Some(Self::Int64),
+            "u64" => Some(Self::UInt64),
+            "f64" => Some(Self::Float64),
+            "u8" => Some(Self::UInt8),
+            _ => None,
+        }
+    }
+
+    /// Every name type annotations can use, aliases follow the type they name.
+    pub const NAMES: [(&'static str, Self); 12] = [
+        ("i64", Self::Int64),
+        ("int", Self::Int64),
+        ("u64", Self::UInt64),
+        ("uint", Self::UInt64),
+        ("f64", Self::Float64),
+        ("float", Self::Float64),
+        ("u8", Self::UInt8),
+        ("byte", Self::UInt8),
+        ("bool", Self::Boolean),
+        ("char", Self::Character),
+        ("string", Self::String),
+        ("range", Self::Range),
+    ];
 
-#[derive(Clone, Debug)]
+    /// Name used by type annotations, like the int in `let x: int = 1`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, ty)| *ty)
+    }
+}
+impl std::fmt::Display for RawTyping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Int64 => "i64",
+            Self::UInt64 => "u64",
+            Self::Float64 => "f64",
+            Self::UInt8 => "u8",
+            Self::Boolean => "bool",
+            Self::Character => "char",
+            Self::String => "string",
+            Self::Range => "range",
+            Self::Function => "fn",
+            Self::Ident => "ident",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum Typing {
     Int64(i64),
     UInt64(u64),
@@ -102,16 +310,229 @@ pub enum Typing {
     Boolean(bool),
 
     Character(char),
-    // TODO: Missing Filler (Should be moved to struct?)
     String(Box<ChoppedString>),
 
+    Range(Box<Range>),
+    Function(FunctionValue),
+
     Ident(Ident),
     // Closure
 }
+impl Typing {
+    #[must_use]
+    pub const fn raw(&self) -> RawTyping {
+        match self {
+            Self::Int64(_) => RawTyping::Int64,
+            Self::UInt64(_) => RawTyping::UInt64,
+            Self::Float64(_) => RawTyping::Float64,
+            Self::UInt8(_) => RawTyping::UInt8,
+            Self::Boolean(_) => RawTyping::Boolean,
+            Self::Character(_) => RawTyping::Character,
+            Self::String(_) => RawTyping::String,
+            Self::Range(_) => RawTyping::Range,
+            Self::Function(_) => RawTyping::Function,
+            Self::Ident(_) => RawTyping::Ident,
+        }
+    }
+}
+impl std::fmt::Display for Typing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int64(v) => write!(f, "{v}"),
+            Self::UInt64(v) => write!(f, "{v}"),
+            Self::Float64(v) => write!(f, "{v}"),
+            Self::UInt8(v) => write!(f, "{v}"),
+            Self::Boolean(v) => write!(f, "{v}"),
+            Self::Character(v) => write!(f, "{v}"),
+            Self::String(v) => write!(f, "{v}"),
+            Self::Range(v) => write!(f, "{v}"),
+            Self::Function(v) => write!(f, "{v}"),
+            Self::Ident(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+/// A declared function, calls run body in a new frame with the params declared.
+#[derive(Debug)]
+pub struct Function {
+    pub name: Ident,
+    pub params: Vec<(Ident, RawTyping)>,
+    /// None when calls have no value.
+    pub ret: Option<RawTyping>,
+    pub body: PosAst,
+}
+/// Functions are only equal to themselves.
+impl PartialEq for Function {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+impl std::fmt::Display for Function {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let params: Vec<_> = self
+            .params
+            .iter()
+            .map(|(id, ty)| format!("{id}: {ty}"))
+            .collect();
+        write!(f, "fn {}({})", self.name, params.join(", "))?;
+        match self.ret {
+            Some(ty) => write!(f, " -> {ty}"),
+            None => Ok(()),
+        }
+    }
+}
 
-#[derive(Clone, Debug)]
+/// A function as a value, siblings are the functions declared in the same block, itself included.
+/// Calls declare the siblings, so functions of a block can call each other.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionValue {
+    pub function: Rc<Function>,
+    pub siblings: Rc<[Rc<Function>]>,
+}
+impl std::fmt::Display for FunctionValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.function)
+    }
+}
+
+/// Integers from start to end, going by step.
+/// A positive step counts up while below end, a negative step counts down while above end.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Range {
+    start: i128,
+    end: i128,
+    inclusive: bool,
+    step: i64,
+    /// Type of start, end and the values.
+    ty: RawTyping,
+}
+impl Range {
+    /// None when start and end are not integers of the same type, or when step is 0.
+    #[must_use]
+    pub fn new(start: &Typing, end: &Typing, inclusive: bool, step: i64) -> Option<Self> {
+        let int = |val: &Typing| match val {
+            Typing::Int64(v) => Some(i128::from(*v)),
+            Typing::UInt64(v) => Some(i128::from(*v)),
+            Typing::UInt8(v) => Some(i128::from(*v)),
+            _ => None,
+        };
+        if start.raw() != end.raw() || step == 0 {
+            return None;
+        }
+        Some(Self {
+            start: int(start)?,
+            end: int(end)?,
+            inclusive,
+            step,
+            ty: start.raw(),
+        })
+    }
+
+    #[must_use]
+    pub fn start(&self) -> Typing {
+        self.value(self.start)
+    }
+
+    #[must_use]
+    pub fn end(&self) -> Typing {
+        self.value(self.end)
+    }
+
+    #[must_use]
+    pub const fn is_inclusive(&self) -> bool {
+        self.inclusive
+    }
+
+    #[must_use]
+    pub const fn step(&self) -> i64 {
+        self.step
+    }
+
+    #[must_use]
+    pub const fn ty(&self) -> RawTyping {
+        self.ty
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Typing> + '_ {
+        let step = i128::from(self.step);
+        std::iter::successors(Some(self.start), move |v| Some(v + step))
+            .take_while(move |v| match (step > 0, self.inclusive) {
+                (true, true) => *v <= self.end,
+                (true, false) => *v < self.end,
+                (false, true) => *v >= self.end,
+                (false, false) => *v > self.end,
+            })
+            .map(|v| self.value(v))
+    }
+
+    /// v is always between start and end, so it fits in ty.
+    fn value(&self, v: i128) -> Typing {
+        match self.ty {
+            RawTyping::UInt64 => Typing::UInt64(v as u64),
+            RawTyping::UInt8 => Typing::UInt8(v as u8),
+            _ => Typing::Int64(v as i64),
+        }
+    }
+}
+impl std::fmt::Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = if self.inclusive { "..=" } else { "..<" };
+        write!(f, "{}{op}{}", self.start, self.end)?;
+        if self.step != 1 {
+            write!(f, " by {}", self.step)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ChoppedString {
     string: String,
     /// [(Ident, Place position)] sorted in reverse order.
     fill: Vec<(Ident, usize)>,
 }
+impl ChoppedString {
+    /// Fill positions are byte offsets into string, and must be sorted in reverse order.
+    #[must_use]
+    pub fn new(string: String, fill: Vec<(Ident, usize)>) -> Self {
+        debug_assert!(fill.windows(2).all(|w| w[0].1 >= w[1].1));
+        Self { string, fill }
+    }
+
+    #[must_use]
+    pub fn string(&self) -> &str {
+        &self.string
+    }
+
+    #[must_use]
+    pub fn fill(&self) -> &[(Ident, usize)] {
+        &self.fill
+    }
+
+    #[must_use]
+    pub fn is_filled(&self) -> bool {
+        self.fill.is_empty()
+    }
+
+    /// Inserts the value of each ident, going from the back so earlier positions stay valid.
+    pub fn fill_with<E>(
+        &self,
+        mut value: impl FnMut(&Ident) -> Result<String, E>,
+    ) -> Result<Self, E> {
+        let mut string = self.string.clone();
+        for (id, place) in &self.fill {
+            string.insert_str(*place, &value(id)?);
+        }
+        Ok(Self::new(string, Vec::new()))
+    }
+}
+impl From<&str> for ChoppedString {
+    fn from(value: &str) -> Self {
+        Self::new(value.to_string(), Vec::new())
+    }
+}
+impl std::fmt::Display for ChoppedString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.string)
+    }
+}
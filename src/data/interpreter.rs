@@ -1,4 +1,4 @@
-use crate::data::{Ident, Typing};
+use crate::data::{DeclarationKind, Ident, RawTyping, Typing};
 use std::collections::HashMap;
 
 // #[derive(Debug)]
@@ -23,12 +23,32 @@ use std::collections::HashMap;
 //     }
 // }
 
-pub type EnvironmentFrame = HashMap<Ident, Typing>;
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variable {
+    pub value: Typing,
+    pub kind: DeclarationKind,
+}
+
+pub type EnvironmentFrame = HashMap<Ident, Variable>;
+
+/// Why Environment::set did not change a variable.
+#[derive(Debug, Eq, PartialEq)]
+pub enum SetError {
+    Undefined,
+    Immutable(DeclarationKind),
+    TypeMismatch {
+        expected: RawTyping,
+        found: RawTyping,
+    },
+}
 
 /// offset functions only search the offset frame.
+/// Inside of a call only the frames of the call and the global frame can be seen.
 #[derive(Debug)]
 pub struct Environment {
     pub frames: Vec<EnvironmentFrame>,
+    /// Index of the first frame of every call, innermost last.
+    calls: Vec<usize>,
 }
 impl Environment {
     #[must_use]
@@ -36,6 +56,7 @@ impl Environment {
         // TODO: Default frame might need KOT constant.
         Self {
             frames: vec![EnvironmentFrame::new()],
+            calls: Vec::new(),
         }
     }
 
@@ -47,41 +68,103 @@ impl Environment {
         self.frames.pop();
     }
 
-    pub fn get(&self, id: &Ident) -> Option<&Typing> {
-        for i in 0..self.frames.len() {
-            let s = self.get_offset(id, i);
-            if s.is_some() {
-                return s;
-            }
+    /// Pushes the first frame of a call, which hides every frame but the global one.
+    pub fn push_call(&mut self) {
+        self.calls.push(self.frames.len());
+        self.push();
+    }
+
+    /// Pops every frame of the innermost call.
+    pub fn pop_call(&mut self) {
+        if let Some(start) = self.calls.pop() {
+            self.frames.truncate(start);
         }
-        None
+    }
+
+    /// Number of calls that have not returned yet.
+    pub fn call_depth(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Offsets of the frames that can be seen, inner frames first.
+    fn visible(&self) -> impl Iterator<Item = usize> {
+        let len = self.frames.len();
+        // Frames of the innermost call, or every frame outside of calls.
+        let inner = self.calls.last().map_or(len, |start| len - start);
+        (0..inner).chain((inner < len).then_some(len - 1))
+    }
+
+    pub fn get(&self, id: &Ident) -> Option<&Typing> {
+        self.get_variable(id).map(|v| &v.value)
     }
     pub fn get_offset(&self, id: &Ident, offset: usize) -> Option<&Typing> {
+        self.get_variable_offset(id, offset).map(|v| &v.value)
+    }
+
+    pub fn get_variable(&self, id: &Ident) -> Option<&Variable> {
+        self.visible()
+            .find_map(|offset| self.get_variable_offset(id, offset))
+    }
+    pub fn get_variable_offset(&self, id: &Ident, offset: usize) -> Option<&Variable> {
         self.frames.get(self.index(offset)).and_then(|f| f.get(id))
     }
 
+    /// Every name that can be looked up, inner frames first.
+    pub fn names(&self) -> impl Iterator<Item = &Ident> {
+        self.visible()
+            .flat_map(|offset| self.frames[self.index(offset)].keys())
+    }
+
+    pub fn get_variable_mut(&mut self, id: &Ident) -> Option<&mut Variable> {
+        let offset = self
+            .visible()
+            .find(|offset| self.contains_offset(id, *offset))?;
+        let i = self.index(offset);
+        self.frames[i].get_mut(id)
+    }
+
     pub fn contains(&self, id: &Ident) -> bool {
-        for i in 0..self.frames.len() {
-            let s = self.contains_offset(id, i);
-            if s {
-                return s;
-            }
-        }
-        false
+        self.visible()
+            .any(|offset| self.contains_offset(id, offset))
     }
     pub fn contains_offset(&self, id: &Ident, offset: usize) -> bool {
         self.frames
             .get(self.index(offset))
-            .map_or(false, |f| f.contains_key(id))
+            .is_some_and(|f| f.contains_key(id))
     }
 
-    /// Sets the var and returns the old value if any
-    pub fn set(&mut self, id: Ident, data: Typing) -> Option<Typing> {
-        self.set_offset(id, data, 0)
+    /// Assigns to the nearest variable that can be seen and returns its old value.
+    /// Only a var can be assigned to, and only with a value of the same type.
+    pub fn set(&mut self, id: &Ident, data: Typing) -> Result<Typing, SetError> {
+        let var = self.get_variable_mut(id).ok_or(SetError::Undefined)?;
+        if !var.kind.is_mutable() {
+            return Err(SetError::Immutable(var.kind));
+        }
+        if var.value.raw() != data.raw() {
+            return Err(SetError::TypeMismatch {
+                expected: var.value.raw(),
+                found: data.raw(),
+            });
+        }
+        Ok(std::mem::replace(&mut var.value, data))
     }
-    pub fn set_offset(&mut self, id: Ident, data: Typing, offset: usize) -> Option<Typing> {
+
+    /// Declares id in the innermost frame, replacing a variable with the same name there.
+    pub fn declare(&mut self, id: Ident, kind: DeclarationKind, data: Typing) -> Option<Typing> {
+        self.declare_offset(id, kind, data, 0)
+    }
+    pub fn declare_offset(
+        &mut self,
+        id: Ident,
+        kind: DeclarationKind,
+        data: Typing,
+        offset: usize,
+    ) -> Option<Typing> {
         let i = self.index(offset);
-        self.frames.get_mut(i).and_then(|f| f.insert(id, data))
+        self.frames
+            .get_mut(i)
+            .and_then(|f| f.insert(id, Variable { value: data, kind }))
+            .map(|v| v.value)
     }
 
     pub fn remove(&mut self, id: &Ident) -> Option<Typing> {
@@ -89,7 +172,10 @@ impl Environment {
     }
     pub fn remove_offset(&mut self, id: &Ident, offset: usize) -> Option<Typing> {
         let i = self.index(offset);
-        self.frames.get_mut(i).and_then(|f| f.remove(id))
+        self.frames
+            .get_mut(i)
+            .and_then(|f| f.remove(id))
+            .map(|v| v.value)
     }
 
     // TODO: Should become debug assert?
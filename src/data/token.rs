@@ -1,55 +1,67 @@
-use crate::{data::Ident, Pos};
+use crate::{
+    data::{ChoppedString, Ident, RawTyping},
+    Pos, Span,
+};
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct PosToken {
     pub token: Token,
     pub pos: Pos,
+    pub span: Span,
 }
 impl PosToken {
     #[must_use]
-    pub const fn new(token: Token, pos: Pos) -> Self {
-        Self { token, pos }
+    pub const fn new(token: Token, pos: Pos, span: Span) -> Self {
+        Self { token, pos, span }
     }
 
     #[must_use]
-    pub const fn eof(pos: Pos) -> Self {
+    pub const fn eof(pos: Pos, span: Span) -> Self {
         Self {
             token: Token::Eof,
             pos,
+            span,
         }
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Token {
     Eof,
 
     /// $ Reserved!
     DollarSign,
 
+    /// /// Documents the item that follows.
+    DocComment(String),
+    /// //! Documents the enclosing item.
+    InnerDocComment(String),
+
     /// Starts with [_ UnicodeLetter], then [_ - UnicodeLetter UnicodeDigit]
     Ident(Ident),
     /// .
     IdentSplit,
     /// #IDENT
     Macro(Ident),
+    /// 'IDENT, names a loop
+    Label(Ident),
 
-    /// 0..=9
-    NumberDecimal(String),
-    /// 0x 0..=F
-    NumberHex(String),
-    /// 0o 0..=8
-    NumberOctal(String),
-    /// 0b 0..=1
-    NumberBinary(String),
+    /// 0..=9, with an optional type suffix
+    NumberDecimal(String, Option<RawTyping>),
+    /// 0x 0..=F, with an optional type suffix
+    NumberHex(String, Option<RawTyping>),
+    /// 0o 0..=7, with an optional type suffix
+    NumberOctal(String, Option<RawTyping>),
+    /// 0b 0..=1, with an optional type suffix
+    NumberBinary(String, Option<RawTyping>),
 
     Character(char),
-    // TODO: String, StringType
-    String(),
+    /// "", #""#, r"" or r#""#
+    String(ChoppedString),
 
     /// true
     True,
-    ///  false
+    /// false
     False,
 
     /// (
@@ -90,16 +102,28 @@ pub enum Token {
 
     /// for
     For,
+    /// in
+    In,
     /// while
     While,
+    /// break
+    Break,
+    /// continue
+    Continue,
 
     /// fn
     Function,
+    /// ret
+    Return,
+    /// ->
+    Arrow,
 
     /// ..<
     RangeExclusive,
     /// ..=
     RangeInclusive,
+    /// by, the step of a range
+    By,
 
     /// =
     Assign,
@@ -117,8 +141,6 @@ pub enum Token {
     AssignBitLeft,
     /// >>=
     AssignBitRight,
-    /// ~=
-    AssignBitNot,
     /// &=
     AssignBitAnd,
     /// ^=
@@ -183,4 +205,89 @@ impl Token {
                 | Self::NumberBinary(..)
         )
     }
+
+    #[must_use]
+    pub const fn is_doc_comment(&self) -> bool {
+        matches!(self, Self::DocComment(..) | Self::InnerDocComment(..))
+    }
+}
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Eof => write!(f, "end of file"),
+            Self::DocComment(_) | Self::InnerDocComment(_) => write!(f, "doc comment"),
+            Self::Ident(id) => write!(f, "identifier '{id}'"),
+            Self::Macro(id) => write!(f, "macro '#{id}'"),
+            Self::Label(id) => write!(f, "label '{id}"),
+            Self::NumberDecimal(num, _) => write!(f, "number '{num}'"),
+            Self::NumberHex(num, _) => write!(f, "number '0x{num}'"),
+            Self::NumberOctal(num, _) => write!(f, "number '0o{num}'"),
+            Self::NumberBinary(num, _) => write!(f, "number '0b{num}'"),
+            Self::Character(c) => write!(f, "character {c:?}"),
+            Self::String(string) => write!(f, "string {:?}", string.string()),
+            Self::DollarSign => write!(f, "'$'"),
+            Self::IdentSplit => write!(f, "'.'"),
+            Self::True => write!(f, "'true'"),
+            Self::False => write!(f, "'false'"),
+            Self::LParentheses => write!(f, "'('"),
+            Self::RParentheses => write!(f, "')'"),
+            Self::LBracket => write!(f, "'['"),
+            Self::RBracket => write!(f, "']'"),
+            Self::LCurly => write!(f, "'{{'"),
+            Self::RCurly => write!(f, "'}}'"),
+            Self::Comma => write!(f, "','"),
+            Self::Colon => write!(f, "':'"),
+            Self::SemiColon => write!(f, "';'"),
+            Self::Const => write!(f, "'const'"),
+            Self::Let => write!(f, "'let'"),
+            Self::Var => write!(f, "'var'"),
+            Self::Cast => write!(f, "'as'"),
+            Self::If => write!(f, "'if'"),
+            Self::Guard => write!(f, "'guard'"),
+            Self::Else => write!(f, "'else'"),
+            Self::For => write!(f, "'for'"),
+            Self::In => write!(f, "'in'"),
+            Self::While => write!(f, "'while'"),
+            Self::Break => write!(f, "'break'"),
+            Self::Continue => write!(f, "'continue'"),
+            Self::Return => write!(f, "'ret'"),
+            Self::Arrow => write!(f, "'->'"),
+            Self::Function => write!(f, "'fn'"),
+            Self::RangeExclusive => write!(f, "'..<'"),
+            Self::RangeInclusive => write!(f, "'..='"),
+            Self::By => write!(f, "'by'"),
+            Self::Assign => write!(f, "'='"),
+            Self::AssignMathMultiply => write!(f, "'*='"),
+            Self::AssignMathDivide => write!(f, "'/='"),
+            Self::AssignMathModulus => write!(f, "'%='"),
+            Self::AssignMathAdd => write!(f, "'+='"),
+            Self::AssignMathSubtract => write!(f, "'-='"),
+            Self::AssignBitLeft => write!(f, "'<<='"),
+            Self::AssignBitRight => write!(f, "'>>='"),
+            Self::AssignBitAnd => write!(f, "'&='"),
+            Self::AssignBitXor => write!(f, "'^='"),
+            Self::AssignBitOr => write!(f, "'|='"),
+            Self::MathMultiply => write!(f, "'*'"),
+            Self::MathDivide => write!(f, "'/'"),
+            Self::MathModulus => write!(f, "'%'"),
+            Self::MathAdd => write!(f, "'+'"),
+            Self::MathSubtract => write!(f, "'-'"),
+            Self::BoolNot => write!(f, "'!'"),
+            Self::BoolAnd => write!(f, "'&&'"),
+            Self::BoolXor => write!(f, "'^^'"),
+            Self::BoolOr => write!(f, "'||'"),
+            Self::CompareEqual => write!(f, "'=='"),
+            Self::CompareNotEqual => write!(f, "'!='"),
+            Self::CompareLess => write!(f, "'<'"),
+            Self::CompareLessEqual => write!(f, "'<='"),
+            Self::CompareGreater => write!(f, "'>'"),
+            Self::CompareGreaterEqual => write!(f, "'>='"),
+            Self::BitNot => write!(f, "'~'"),
+            Self::BitLeft => write!(f, "'<<'"),
+            Self::BitRight => write!(f, "'>>'"),
+            Self::BitAnd => write!(f, "'&'"),
+            Self::BitXor => write!(f, "'^'"),
+            Self::BitOr => write!(f, "'|'"),
+        }
+    }
 }
@@ -0,0 +1,499 @@
+//! Renders lexer, parser and runtime errors against the source they came from.
+
+use crate::{data::RawTyping, LexerError, ParseError, Pos, RuntimeError, SourceFile, Span};
+use std::fmt::Write;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Label {
+    pub pos: Pos,
+    /// Source text underlined to the end of its first line, an empty span only marks pos.
+    pub span: Span,
+    pub message: String,
+    /// Primary labels mark where the error is, secondary labels add context.
+    pub primary: bool,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+impl Diagnostic {
+    #[must_use]
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn with_label(self, pos: Pos, message: impl Into<String>) -> Self {
+        self.with_label_span(pos, Span::default(), message)
+    }
+
+    /// Same as with_label, but underlines the text of span.
+    #[must_use]
+    pub fn with_label_span(mut self, pos: Pos, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            pos,
+            span,
+            message: message.into(),
+            primary: true,
+        });
+        self
+    }
+
+    #[must_use]
+    pub fn with_secondary(self, pos: Pos, message: impl Into<String>) -> Self {
+        self.with_secondary_span(pos, Span::default(), message)
+    }
+
+    /// Same as with_secondary, but underlines the text of span.
+    #[must_use]
+    pub fn with_secondary_span(mut self, pos: Pos, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            pos,
+            span,
+            message: message.into(),
+            primary: false,
+        });
+        self
+    }
+
+    #[must_use]
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Color uses ANSI escapes, only turn it on when writing to a terminal.
+    #[must_use]
+    pub fn render(&self, source: &str, color: bool) -> String {
+        self.render_named(None, &SourceFile::new("", source), color)
+    }
+
+    /// Same as render, but points at the file name too.
+    #[must_use]
+    pub fn render_file(&self, file: &SourceFile, color: bool) -> String {
+        self.render_named(Some(file.name()), file, color)
+    }
+
+    fn render_named(&self, name: Option<&str>, file: &SourceFile, color: bool) -> String {
+        let style = Style::new(color);
+        let mut lines: Vec<&str> = file.text().lines().collect();
+        if lines.is_empty() {
+            lines.push("");
+        }
+
+        let mut labels: Vec<(usize, usize, usize, &Label)> = self
+            .labels
+            .iter()
+            .map(|label| {
+                let (line, col, len) = underline(file, &lines, label);
+                (line, col, len, label)
+            })
+            .collect();
+        labels.sort_by_key(|(line, col, ..)| (*line, *col));
+
+        let width = labels
+            .iter()
+            .map(|(line, ..)| line.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let pad = " ".repeat(width);
+        let Style {
+            error,
+            primary,
+            secondary,
+            gutter,
+            bold,
+            reset,
+        } = style;
+
+        let mut out = String::new();
+        let _ = writeln!(out, "{error}error{reset}{bold}: {}{reset}", self.message);
+        // Points at the pos of the message, which can be inside of the underline.
+        if let Some((.., label)) = labels
+            .iter()
+            .find(|(.., label)| label.primary)
+            .or(labels.first())
+        {
+            let (line, col) = clamp(&lines, label.pos);
+            let _ = match name {
+                Some(name) => writeln!(out, "{pad}{gutter}-->{reset} {name}:{line}:{col}"),
+                None => writeln!(out, "{pad}{gutter}-->{reset} ({line}:{col})"),
+            };
+        }
+
+        if !labels.is_empty() {
+            let _ = writeln!(out, "{pad} {gutter}|{reset}");
+        }
+        let mut last_line = None;
+        for (line, col, len, label) in &labels {
+            let text = lines[line - 1];
+            if last_line != Some(*line) {
+                if last_line.is_some_and(|last| line - last > 1) {
+                    let _ = writeln!(out, "{gutter}...{reset}");
+                }
+                let _ = writeln!(out, "{gutter}{line:>width$} |{reset} {text}");
+                last_line = Some(*line);
+            }
+
+            // Tabs are kept so the marker lines up with the source.
+            let indent: String = text
+                .chars()
+                .take(col - 1)
+                .map(|c| {
+                    if c == '\t' {
+                        '\t'
+                    }
+                    else {
+                        ' '
+                    }
+                })
+                .collect();
+            let (mark, mark_style) = if label.primary { ('^', primary) } else { ('-', secondary) };
+            // Stops at the end of the line, the end of file still gets one mark.
+            let len = (*len).min(text.chars().count() + 1 - col).max(1);
+            let marks = mark.to_string().repeat(len);
+            let _ = writeln!(
+                out,
+                "{pad} {gutter}|{reset} {indent}{mark_style}{marks} {}{reset}",
+                label.message
+            );
+        }
+        if !labels.is_empty() && (!self.notes.is_empty() || self.help.is_some()) {
+            let _ = writeln!(out, "{pad} {gutter}|{reset}");
+        }
+
+        for note in &self.notes {
+            let _ = writeln!(out, "{pad} {gutter}={reset} {bold}note{reset}: {note}");
+        }
+        if let Some(help) = &self.help {
+            let _ = writeln!(out, "{pad} {gutter}={reset} {bold}help{reset}: {help}");
+        }
+
+        out
+    }
+}
+
+/// Line, column and chars underlined for a label, which is the first line of its span.
+/// Labels without a span in the source mark one char at their pos.
+fn underline(file: &SourceFile, lines: &[&str], label: &Label) -> (usize, usize, usize) {
+    let span = Span::new(label.span.start, label.span.end.min(file.text().len()));
+    let text = (!span.is_empty()).then(|| file.slice(span)).flatten();
+    match (text, file.pos(span.start)) {
+        (Some(text), Some(pos)) => {
+            let (line, col) = clamp(lines, pos);
+            let len = text.lines().next().map_or(0, |line| line.chars().count());
+            (line, col, len)
+        }
+        _ => {
+            let (line, col) = clamp(lines, label.pos);
+            (line, col, 1)
+        }
+    }
+}
+
+/// Positions past the end of the source, like the end of file, point after the last character.
+fn clamp(lines: &[&str], pos: Pos) -> (usize, usize) {
+    if pos.line() > lines.len() {
+        let line = lines.len();
+        return (line, lines[line - 1].chars().count() + 1);
+    }
+    let line = pos.line().max(1);
+    let col = pos.col().clamp(1, lines[line - 1].chars().count() + 1);
+    (line, col)
+}
+
+#[derive(Clone, Copy)]
+struct Style {
+    error: &'static str,
+    primary: &'static str,
+    secondary: &'static str,
+    gutter: &'static str,
+    bold: &'static str,
+    reset: &'static str,
+}
+impl Style {
+    const fn new(color: bool) -> Self {
+        if color {
+            Self {
+                error: "\x1b[1;31m",
+                primary: "\x1b[1;31m",
+                secondary: "\x1b[1;34m",
+                gutter: "\x1b[1;34m",
+                bold: "\x1b[1m",
+                reset: "\x1b[0m",
+            }
+        }
+        else {
+            Self {
+                error: "",
+                primary: "",
+                secondary: "",
+                gutter: "",
+                bold: "",
+                reset: "",
+            }
+        }
+    }
+}
+
+/// Renders any error from lex, parse or run, other errors only show their message.
+#[must_use]
+pub fn render(source: &str, err: &anyhow::Error, color: bool) -> String {
+    diagnostic(err).render(source, color)
+}
+
+/// Same as render, but points at the file name too.
+#[must_use]
+pub fn render_file(file: &SourceFile, err: &anyhow::Error, color: bool) -> String {
+    diagnostic(err).render_file(file, color)
+}
+
+fn diagnostic(err: &anyhow::Error) -> Diagnostic {
+    if let Some(err) = err.downcast_ref::<LexerError>() {
+        Diagnostic::from(err)
+    }
+    else if let Some(err) = err.downcast_ref::<ParseError>() {
+        Diagnostic::from(err)
+    }
+    else if let Some(err) = err.downcast_ref::<RuntimeError>() {
+        Diagnostic::from(err)
+    }
+    else {
+        Diagnostic::new(err.to_string())
+    }
+}
+
+impl From<&LexerError> for Diagnostic {
+    fn from(err: &LexerError) -> Self {
+        let diagnostic = Self::new(err.to_string());
+        match err {
+            LexerError::UnexpectedChar { pos, .. } => {
+                diagnostic.with_label(*pos, "not part of any token")
+            }
+            LexerError::DecimalBadToken { pos, .. } => diagnostic.with_label(*pos, "bad token"),
+            LexerError::DecimalMoreThanOnePeriod { pos } => {
+                diagnostic.with_label(*pos, "second period")
+            }
+            LexerError::MacroBadIdent { pos, .. } => {
+                diagnostic.with_label(*pos, "macros can only use _ and ascii characters")
+            }
+            LexerError::BlockCommentUnterminated { pos } => diagnostic
+                .with_label(*pos, "comment starts here")
+                .with_help("close it with */, block comments nest"),
+            LexerError::NumberBadDigit { radix, pos, .. } => {
+                diagnostic.with_label(*pos, format!("not a base {radix} digit"))
+            }
+            LexerError::NumberMissingDigits { pos, .. } => {
+                diagnostic.with_label(*pos, "expected digits")
+            }
+            LexerError::NumberOutOfRange { pos, .. } => {
+                diagnostic.with_label(*pos, "does not fit in 64 bits")
+            }
+            LexerError::NumberBadSuffix { pos, .. } => diagnostic
+                .with_label(*pos, "unknown suffix")
+                .with_help("use one of u8, u64, i64 or f64"),
+            LexerError::CharEmpty { pos } => diagnostic.with_label(*pos, "empty character"),
+            LexerError::CharMultiple { pos } => diagnostic
+                .with_label(*pos, "more than one character")
+                .with_help("use \" for a string"),
+            LexerError::CharUnterminated { pos } => {
+                diagnostic.with_label(*pos, "character starts here")
+            }
+            LexerError::EscapeBad { pos, .. } => diagnostic
+                .with_label(*pos, "unknown escape")
+                .with_help("valid escapes are \\n \\r \\t \\0 \\\\ \\' \\\" \\{ \\} and \\u{...}"),
+            LexerError::EscapeBadUnicode { pos } => diagnostic
+                .with_label(*pos, "invalid unicode escape")
+                .with_help("use \\u{...} with 1 to 6 hex digits of a valid code point"),
+            LexerError::StringMissingQuote { pos } => diagnostic.with_label(*pos, "expected '\"'"),
+            LexerError::StringUnterminated { pos } => {
+                diagnostic.with_label(*pos, "string starts here")
+            }
+            LexerError::StringBadInterpolation { pos } => diagnostic
+                .with_label(*pos, "invalid interpolation")
+                .with_help("use \\{ for a literal {"),
+        }
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(err: &ParseError) -> Self {
+        let diagnostic = Self::new(err.to_string());
+        let (pos, span) = (err.pos(), err.span());
+        match err {
+            ParseError::ExpectedToken { expected, .. } => {
+                diagnostic.with_label_span(pos, span, format!("expected {expected}"))
+            }
+            ParseError::ExpectedExpression { .. } => {
+                diagnostic.with_label_span(pos, span, "expected an expression")
+            }
+            ParseError::ExpectedIdent { .. } => {
+                diagnostic.with_label_span(pos, span, "expected an identifier")
+            }
+            ParseError::UnknownType { .. } => diagnostic
+                .with_label_span(pos, span, "unknown type")
+                .with_help(format!("use one of {}", type_names())),
+            ParseError::InvalidAssignTarget { .. } => {
+                diagnostic.with_label_span(pos, span, "the left side is not a variable")
+            }
+            ParseError::GuardNotDiverging { .. } => diagnostic
+                .with_label_span(pos, span, "can reach the end of this block")
+                .with_help("end the block with ret, break or continue"),
+            ParseError::OutsideLoop { found, .. } => {
+                diagnostic.with_label_span(pos, span, format!("{found} outside of a loop"))
+            }
+            ParseError::UndefinedLabel { .. } => {
+                diagnostic.with_label_span(pos, span, "no enclosing loop has this label")
+            }
+            ParseError::DuplicateParameter { .. } => {
+                diagnostic.with_label_span(pos, span, "second parameter with this name")
+            }
+            ParseError::UnexpectedReturnValue { .. } => diagnostic
+                .with_label_span(pos, span, "value of a function without a return type")
+                .with_help("add a return type after `->`, or use ret without a value"),
+            ParseError::ConstNotConstant { .. } => diagnostic
+                .with_label_span(pos, span, "uses a variable")
+                .with_help("use let for values that are only known when the script runs"),
+            ParseError::RequireParentheses {
+                first,
+                first_pos,
+                first_span,
+                suggestion,
+                ..
+            } => diagnostic
+                .with_label_span(pos, span, "can not be chained")
+                .with_secondary_span(*first_pos, *first_span, format!("with this {first}"))
+                .with_help(format!("add parentheses like `{suggestion}`")),
+            ParseError::NumberOutOfRange { ty, .. } => {
+                diagnostic.with_label_span(pos, span, format!("does not fit in {ty}"))
+            }
+            ParseError::NumberFloatSuffix { .. } => diagnostic
+                .with_label_span(pos, span, "float with an integer suffix")
+                .with_help("floats can only use the f64 suffix"),
+            ParseError::NestingDepth { .. } => diagnostic
+                .with_label_span(pos, span, "nested too deep")
+                .with_help("move parts of the expression into variables or functions"),
+        }
+    }
+}
+
+impl From<&RuntimeError> for Diagnostic {
+    fn from(err: &RuntimeError) -> Self {
+        let root = err.root();
+        let mut diagnostic = Self::new(root.to_string());
+        let (pos, span) = (root.pos(), root.span());
+        diagnostic = match root {
+            RuntimeError::InFunction { .. } => unreachable!(),
+            RuntimeError::ExpectedValue { .. } => {
+                diagnostic.with_label_span(pos, span, "has no value")
+            }
+            RuntimeError::UndefinedVariable { suggestions, .. } => {
+                let diagnostic = diagnostic.with_label_span(pos, span, "undefined variable");
+                match suggestions.as_slice() {
+                    [] => diagnostic,
+                    [name] => diagnostic
+                        .with_help(format!("a variable with a similar name exists: '{name}'")),
+                    names => diagnostic.with_help(format!(
+                        "variables with similar names exist: '{}'",
+                        names.join("', '")
+                    )),
+                }
+            }
+            RuntimeError::Redeclared { .. } => diagnostic
+                .with_label_span(pos, span, "already declared in this scope")
+                .with_help("use var and assign to it instead"),
+            RuntimeError::AssignImmutable { kind, .. } => diagnostic
+                .with_label_span(pos, span, format!("declared with {kind}"))
+                .with_help("declare it with var to make it mutable"),
+            RuntimeError::VariableTypeMismatch {
+                expected, found, ..
+            } => {
+                diagnostic.with_label_span(pos, span, format!("expected {expected}, found {found}"))
+            }
+            RuntimeError::ConditionNotBoolean { found, .. } => diagnostic
+                .with_label_span(pos, span, format!("expected bool, found {found}"))
+                .with_help("compare the value, like `x != 0`"),
+            RuntimeError::RangeBounds { start, end, .. } => {
+                diagnostic.with_label_span(pos, span, format!("range from {start} to {end}"))
+            }
+            RuntimeError::RangeStepType { found, .. } => diagnostic
+                .with_label_span(pos, span, format!("step is {found}"))
+                .with_help("the step is always an i64, `by -1` counts down"),
+            RuntimeError::RangeStepZero { .. } => diagnostic
+                .with_label_span(pos, span, "step of 0")
+                .with_help("use `by -1` to count down"),
+            RuntimeError::NotIterable { found, .. } => {
+                diagnostic.with_label_span(pos, span, format!("{found} is not a range"))
+            }
+            RuntimeError::NotCallable { found, .. } => {
+                diagnostic.with_label_span(pos, span, format!("{found} is not a function"))
+            }
+            RuntimeError::ArgumentCount {
+                expected, found, ..
+            } => diagnostic.with_label_span(
+                pos,
+                span,
+                format!("expected {expected} arguments, found {found}"),
+            ),
+            RuntimeError::ReturnTypeMismatch {
+                expected, found, ..
+            } => {
+                let found = found.map_or_else(|| "no value".to_string(), |ty| ty.to_string());
+                diagnostic.with_label_span(pos, span, format!("expected {expected}, found {found}"))
+            }
+            RuntimeError::CallDepth { .. } => diagnostic
+                .with_label_span(pos, span, "too many calls inside of each other")
+                .with_help("recursion needs a case that does not call the function again"),
+            RuntimeError::NestingDepth { .. } => diagnostic
+                .with_label_span(
+                    pos,
+                    span,
+                    "runs too deep inside of other expressions and calls",
+                )
+                .with_help("split the expression or call less deep"),
+            RuntimeError::UnaryTypeMismatch { ty, .. } => {
+                diagnostic.with_label_span(pos, span, format!("operand is {ty}"))
+            }
+            RuntimeError::BinaryTypeMismatch { lhs, rhs, .. } => {
+                diagnostic.with_label_span(pos, span, format!("{lhs} and {rhs}"))
+            }
+            RuntimeError::Overflow { ty, .. } => {
+                diagnostic.with_label_span(pos, span, format!("result does not fit in {ty}"))
+            }
+            RuntimeError::DivideByZero { .. } => {
+                diagnostic.with_label_span(pos, span, "divided by zero")
+            }
+            RuntimeError::ShiftOutOfRange { amount, .. } => diagnostic.with_label_span(
+                pos,
+                span,
+                format!("shift amount {amount} is out of range"),
+            ),
+        };
+        for (function, call_pos, call_span) in err.trace() {
+            diagnostic = diagnostic
+                .with_secondary_span(call_pos, call_span, format!("in fn {function} called here"))
+                .with_note(format!("in fn {function} called from {call_pos}"));
+        }
+        diagnostic
+    }
+}
+
+/// Names of RawTyping::NAMES, like `a, b or c`.
+fn type_names() -> String {
+    let names: Vec<&str> = RawTyping::NAMES.iter().map(|(name, _)| *name).collect();
+    let (last, rest) = names.split_last().unwrap();
+    format!("{} or {last}", rest.join(", "))
+}
@@ -1 +1,228 @@
+use crate::{
+    data::{BinaryOperation, RawTyping, Typing},
+    interpreter::RuntimeError,
+    Pos, Span,
+};
 
+/// Same typed integer math, comparison and bitwise operators.
+/// Math is checked, overflow and dividing by zero are errors.
+macro_rules! int_op {
+    ($op:ident, $i1:ident, $i2:ident, $t:ident, $pos:ident, $span:ident) => {{
+        let ty = RawTyping::$t;
+        match $op {
+            BinaryOperation::Divide | BinaryOperation::Modulus if $i2 == 0 => {
+                Err(RuntimeError::DivideByZero {
+                    pos: $pos,
+                    span: $span,
+                })
+            }
+            BinaryOperation::Multiply => {
+                overflow($i1.checked_mul($i2).map(Typing::$t), ty, $pos, $span)
+            }
+            BinaryOperation::Divide => {
+                overflow($i1.checked_div($i2).map(Typing::$t), ty, $pos, $span)
+            }
+            BinaryOperation::Modulus => {
+                overflow($i1.checked_rem($i2).map(Typing::$t), ty, $pos, $span)
+            }
+            BinaryOperation::Add => overflow($i1.checked_add($i2).map(Typing::$t), ty, $pos, $span),
+            BinaryOperation::Subtract => {
+                overflow($i1.checked_sub($i2).map(Typing::$t), ty, $pos, $span)
+            }
+            BinaryOperation::BitwiseAnd => Ok(Some(Typing::$t($i1 & $i2))),
+            BinaryOperation::BitwiseXor => Ok(Some(Typing::$t($i1 ^ $i2))),
+            BinaryOperation::BitwiseOr => Ok(Some(Typing::$t($i1 | $i2))),
+            _ => Ok(compare($op, &$i1, &$i2)),
+        }
+    }};
+}
+
+/// See docs/OPERATORS.md for which types each operator supports.
+pub fn binary_op(
+    op: BinaryOperation,
+    lhs: Typing,
+    rhs: Typing,
+    pos: Pos,
+    span: Span,
+) -> Result<Typing, RuntimeError> {
+    let (lhs_ty, rhs_ty) = (lhs.raw(), rhs.raw());
+    let ret = match (lhs, rhs) {
+        (lhs, rhs) if is_shift(op) => shift(op, lhs, rhs, pos, span)?,
+        (Typing::Int64(i1), Typing::Int64(i2)) => int_op!(op, i1, i2, Int64, pos, span)?,
+        (Typing::UInt64(i1), Typing::UInt64(i2)) => int_op!(op, i1, i2, UInt64, pos, span)?,
+        (Typing::UInt8(i1), Typing::UInt8(i2)) => int_op!(op, i1, i2, UInt8, pos, span)?,
+        (Typing::Float64(f1), Typing::Float64(f2)) => float64(op, f1, f2),
+        (Typing::Float64(f1), rhs) => as_float(&rhs).and_then(|f2| float64(op, f1, f2)),
+        (lhs, Typing::Float64(f2)) => as_float(&lhs).and_then(|f1| float64(op, f1, f2)),
+        (Typing::Boolean(b1), Typing::Boolean(b2)) => boolean(op, b1, b2),
+        (Typing::Character(c1), Typing::Character(c2)) => compare(op, &c1, &c2),
+        (Typing::String(s1), Typing::String(s2)) => compare(op, s1.string(), s2.string()),
+        _ => None,
+    };
+
+    ret.ok_or(RuntimeError::BinaryTypeMismatch {
+        op,
+        lhs: lhs_ty,
+        rhs: rhs_ty,
+        pos,
+        span,
+    })
+}
+
+/// Like binary_op, but `+`, `-` and `*` wrap around on overflow.
+pub fn wrapping_op(
+    op: BinaryOperation,
+    lhs: Typing,
+    rhs: Typing,
+    pos: Pos,
+    span: Span,
+) -> Result<Typing, RuntimeError> {
+    macro_rules! wrapping {
+        ($i1:ident, $i2:ident, $t:ident) => {{
+            match op {
+                BinaryOperation::Multiply => Ok(Typing::$t($i1.wrapping_mul($i2))),
+                BinaryOperation::Add => Ok(Typing::$t($i1.wrapping_add($i2))),
+                BinaryOperation::Subtract => Ok(Typing::$t($i1.wrapping_sub($i2))),
+                _ => binary_op(op, Typing::$t($i1), Typing::$t($i2), pos, span),
+            }
+        }};
+    }
+
+    match (lhs, rhs) {
+        (Typing::Int64(i1), Typing::Int64(i2)) => wrapping!(i1, i2, Int64),
+        (Typing::UInt64(i1), Typing::UInt64(i2)) => wrapping!(i1, i2, UInt64),
+        (Typing::UInt8(i1), Typing::UInt8(i2)) => wrapping!(i1, i2, UInt8),
+        (lhs, rhs) => binary_op(op, lhs, rhs, pos, span),
+    }
+}
+
+/// Like binary_op, but `+`, `-` and `*` stop at the bounds of the type on overflow.
+pub fn saturating_op(
+    op: BinaryOperation,
+    lhs: Typing,
+    rhs: Typing,
+    pos: Pos,
+    span: Span,
+) -> Result<Typing, RuntimeError> {
+    macro_rules! saturating {
+        ($i1:ident, $i2:ident, $t:ident) => {{
+            match op {
+                BinaryOperation::Multiply => Ok(Typing::$t($i1.saturating_mul($i2))),
+                BinaryOperation::Add => Ok(Typing::$t($i1.saturating_add($i2))),
+                BinaryOperation::Subtract => Ok(Typing::$t($i1.saturating_sub($i2))),
+                _ => binary_op(op, Typing::$t($i1), Typing::$t($i2), pos, span),
+            }
+        }};
+    }
+
+    match (lhs, rhs) {
+        (Typing::Int64(i1), Typing::Int64(i2)) => saturating!(i1, i2, Int64),
+        (Typing::UInt64(i1), Typing::UInt64(i2)) => saturating!(i1, i2, UInt64),
+        (Typing::UInt8(i1), Typing::UInt8(i2)) => saturating!(i1, i2, UInt8),
+        (lhs, rhs) => binary_op(op, lhs, rhs, pos, span),
+    }
+}
+
+fn overflow(
+    val: Option<Typing>,
+    ty: RawTyping,
+    pos: Pos,
+    span: Span,
+) -> Result<Option<Typing>, RuntimeError> {
+    val.map(Some)
+        .ok_or(RuntimeError::Overflow { ty, pos, span })
+}
+
+const fn is_shift(op: BinaryOperation) -> bool {
+    matches!(
+        op,
+        BinaryOperation::BitwiseShiftLeft | BinaryOperation::BitwiseShiftRight
+    )
+}
+
+/// The left side keeps its type, the amount can be any integer type.
+/// Shifting by the bit width of the type or more is an error.
+fn shift(
+    op: BinaryOperation,
+    lhs: Typing,
+    rhs: Typing,
+    pos: Pos,
+    span: Span,
+) -> Result<Option<Typing>, RuntimeError> {
+    let amount = match rhs {
+        Typing::Int64(i) => i128::from(i),
+        Typing::UInt64(i) => i128::from(i),
+        Typing::UInt8(i) => i128::from(i),
+        _ => return Ok(None),
+    };
+
+    macro_rules! shift {
+        ($i:ident, $t:ident) => {{
+            let shifted = u32::try_from(amount).ok().and_then(|amount| match op {
+                BinaryOperation::BitwiseShiftLeft => $i.checked_shl(amount),
+                _ => $i.checked_shr(amount),
+            });
+            match shifted {
+                Some(v) => Ok(Some(Typing::$t(v))),
+                None => Err(RuntimeError::ShiftOutOfRange {
+                    amount,
+                    ty: RawTyping::$t,
+                    pos,
+                    span,
+                }),
+            }
+        }};
+    }
+
+    match lhs {
+        Typing::Int64(i) => shift!(i, Int64),
+        Typing::UInt64(i) => shift!(i, UInt64),
+        Typing::UInt8(i) => shift!(i, UInt8),
+        _ => Ok(None),
+    }
+}
+
+/// Follows IEEE 754, see docs/NUMBERS.md.
+fn float64(op: BinaryOperation, f1: f64, f2: f64) -> Option<Typing> {
+    match op {
+        BinaryOperation::Multiply => Some(Typing::Float64(f1 * f2)),
+        BinaryOperation::Divide => Some(Typing::Float64(f1 / f2)),
+        BinaryOperation::Modulus => Some(Typing::Float64(f1 % f2)),
+        BinaryOperation::Add => Some(Typing::Float64(f1 + f2)),
+        BinaryOperation::Subtract => Some(Typing::Float64(f1 - f2)),
+        _ => compare(op, &f1, &f2),
+    }
+}
+
+/// Mixed math promotes the integer to a float.
+#[allow(clippy::cast_precision_loss)]
+fn as_float(val: &Typing) -> Option<f64> {
+    match val {
+        Typing::Int64(i) => Some(*i as f64),
+        Typing::UInt64(i) => Some(*i as f64),
+        Typing::UInt8(i) => Some(f64::from(*i)),
+        _ => None,
+    }
+}
+
+/// `&&` and `||` only get here when the left side did not short circuit.
+fn boolean(op: BinaryOperation, b1: bool, b2: bool) -> Option<Typing> {
+    match op {
+        BinaryOperation::BooleanAnd | BinaryOperation::BitwiseAnd => Some(Typing::Boolean(b1 & b2)),
+        BinaryOperation::BooleanXor | BinaryOperation::BitwiseXor => Some(Typing::Boolean(b1 ^ b2)),
+        BinaryOperation::BooleanOr | BinaryOperation::BitwiseOr => Some(Typing::Boolean(b1 | b2)),
+        _ => compare(op, &b1, &b2),
+    }
+}
+
+fn compare<T: PartialOrd + ?Sized>(op: BinaryOperation, v1: &T, v2: &T) -> Option<Typing> {
+    match op {
+        BinaryOperation::Equal => Some(Typing::Boolean(v1 == v2)),
+        BinaryOperation::NotEqual => Some(Typing::Boolean(v1 != v2)),
+        BinaryOperation::Less => Some(Typing::Boolean(v1 < v2)),
+        BinaryOperation::LessEqual => Some(Typing::Boolean(v1 <= v2)),
+        BinaryOperation::Greater => Some(Typing::Boolean(v1 > v2)),
+        BinaryOperation::GreaterEqual => Some(Typing::Boolean(v1 >= v2)),
+        _ => None,
+    }
+}
@@ -1,64 +1,1085 @@
-mod binary_ops;
+pub mod binary_ops;
+mod suggest;
 mod unary_ops;
 
-use crate::data::{Ast, BinaryOperation, Environment, PosAst, Typing, UnaryOperation};
+use crate::{
+    data::{
+        Ast, BinaryOperation, ChoppedString, DeclarationKind, Environment, Function, FunctionValue,
+        Ident, PosAst, Range, RawTyping, SetError, Typing, UnaryOperation,
+    },
+    Pos, Span,
+};
+use std::{ops::ControlFlow, rc::Rc};
 
-// TODO: Change!!!
-pub fn run(ast: &PosAst, interp: &mut Interpreter) -> anyhow::Result<Option<Typing>> {
-    todo!()
+/// The pos and span are always of the node that failed, calls wrap the error in InFunction.
+#[derive(Debug, Eq, PartialEq)]
+pub enum RuntimeError {
+    /// The error happened inside of function, which was called at call_pos and call_span.
+    InFunction {
+        function: Ident,
+        call_pos: Pos,
+        call_span: Span,
+        error: Box<Self>,
+    },
+    ExpectedValue {
+        pos: Pos,
+        span: Span,
+    },
+    /// Suggestions are visible names that are close to id.
+    UndefinedVariable {
+        id: Ident,
+        pos: Pos,
+        span: Span,
+        suggestions: Vec<Ident>,
+    },
+    /// Declared twice in the same scope.
+    Redeclared {
+        id: Ident,
+        pos: Pos,
+        span: Span,
+    },
+    AssignImmutable {
+        id: Ident,
+        kind: DeclarationKind,
+        pos: Pos,
+        span: Span,
+    },
+    /// The value does not match the annotated type, or the type a var was declared with.
+    VariableTypeMismatch {
+        id: Ident,
+        expected: RawTyping,
+        found: RawTyping,
+        pos: Pos,
+        span: Span,
+    },
+    ConditionNotBoolean {
+        found: RawTyping,
+        pos: Pos,
+        span: Span,
+    },
+    /// Start and end must be integers of the same type.
+    RangeBounds {
+        start: RawTyping,
+        end: RawTyping,
+        pos: Pos,
+        span: Span,
+    },
+    /// The step must be an i64.
+    RangeStepType {
+        found: RawTyping,
+        pos: Pos,
+        span: Span,
+    },
+    RangeStepZero {
+        pos: Pos,
+        span: Span,
+    },
+    NotIterable {
+        found: RawTyping,
+        pos: Pos,
+        span: Span,
+    },
+    NotCallable {
+        id: Ident,
+        found: RawTyping,
+        pos: Pos,
+        span: Span,
+    },
+    ArgumentCount {
+        function: Ident,
+        expected: usize,
+        found: usize,
+        pos: Pos,
+        span: Span,
+    },
+    /// found is None when the call has no value.
+    ReturnTypeMismatch {
+        function: Ident,
+        expected: RawTyping,
+        found: Option<RawTyping>,
+        pos: Pos,
+        span: Span,
+    },
+    /// Not wrapped in InFunction, the trace would have a frame for every call.
+    /// limit is the Interpreter::max_call_depth that was reached.
+    CallDepth {
+        function: Ident,
+        limit: usize,
+        pos: Pos,
+        span: Span,
+    },
+    /// Not wrapped in InFunction, like CallDepth.
+    /// limit is the Interpreter::max_depth that was reached.
+    NestingDepth {
+        limit: usize,
+        pos: Pos,
+        span: Span,
+    },
+    UnaryTypeMismatch {
+        op: UnaryOperation,
+        ty: RawTyping,
+        pos: Pos,
+        span: Span,
+    },
+    BinaryTypeMismatch {
+        op: BinaryOperation,
+        lhs: RawTyping,
+        rhs: RawTyping,
+        pos: Pos,
+        span: Span,
+    },
+    Overflow {
+        ty: RawTyping,
+        pos: Pos,
+        span: Span,
+    },
+    DivideByZero {
+        pos: Pos,
+        span: Span,
+    },
+    ShiftOutOfRange {
+        amount: i128,
+        ty: RawTyping,
+        pos: Pos,
+        span: Span,
+    },
+}
+impl RuntimeError {
+    /// Adds a call frame, the first frame added is the innermost call.
+    #[must_use]
+    pub fn in_function(self, function: Ident, call_pos: Pos, call_span: Span) -> Self {
+        Self::InFunction {
+            function,
+            call_pos,
+            call_span,
+            error: Box::new(self),
+        }
+    }
+
+    /// The error without any call frames.
+    #[must_use]
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::InFunction { error, .. } => error.root(),
+            err => err,
+        }
+    }
+
+    /// Pos of the node that failed.
+    #[must_use]
+    pub fn pos(&self) -> Pos {
+        match self.root() {
+            Self::InFunction { .. } => unreachable!(),
+            Self::ExpectedValue { pos, .. }
+            | Self::UndefinedVariable { pos, .. }
+            | Self::Redeclared { pos, .. }
+            | Self::AssignImmutable { pos, .. }
+            | Self::VariableTypeMismatch { pos, .. }
+            | Self::ConditionNotBoolean { pos, .. }
+            | Self::RangeBounds { pos, .. }
+            | Self::RangeStepType { pos, .. }
+            | Self::RangeStepZero { pos, .. }
+            | Self::NotIterable { pos, .. }
+            | Self::NotCallable { pos, .. }
+            | Self::ArgumentCount { pos, .. }
+            | Self::ReturnTypeMismatch { pos, .. }
+            | Self::CallDepth { pos, .. }
+            | Self::NestingDepth { pos, .. }
+            | Self::UnaryTypeMismatch { pos, .. }
+            | Self::BinaryTypeMismatch { pos, .. }
+            | Self::Overflow { pos, .. }
+            | Self::DivideByZero { pos, .. }
+            | Self::ShiftOutOfRange { pos, .. } => *pos,
+        }
+    }
+
+    /// Span of the node that failed.
+    #[must_use]
+    pub fn span(&self) -> Span {
+        match self.root() {
+            Self::InFunction { .. } => unreachable!(),
+            Self::ExpectedValue { span, .. }
+            | Self::UndefinedVariable { span, .. }
+            | Self::Redeclared { span, .. }
+            | Self::AssignImmutable { span, .. }
+            | Self::VariableTypeMismatch { span, .. }
+            | Self::ConditionNotBoolean { span, .. }
+            | Self::RangeBounds { span, .. }
+            | Self::RangeStepType { span, .. }
+            | Self::RangeStepZero { span, .. }
+            | Self::NotIterable { span, .. }
+            | Self::NotCallable { span, .. }
+            | Self::ArgumentCount { span, .. }
+            | Self::ReturnTypeMismatch { span, .. }
+            | Self::CallDepth { span, .. }
+            | Self::NestingDepth { span, .. }
+            | Self::UnaryTypeMismatch { span, .. }
+            | Self::BinaryTypeMismatch { span, .. }
+            | Self::Overflow { span, .. }
+            | Self::DivideByZero { span, .. }
+            | Self::ShiftOutOfRange { span, .. } => *span,
+        }
+    }
+
+    /// Call frames as (function, call pos, call span), innermost call first.
+    #[must_use]
+    pub fn trace(&self) -> Vec<(&Ident, Pos, Span)> {
+        let mut trace = Vec::new();
+        let mut err = self;
+        while let Self::InFunction {
+            function,
+            call_pos,
+            call_span,
+            error,
+        } = err
+        {
+            trace.push((function, *call_pos, *call_span));
+            err = error;
+        }
+        trace.reverse();
+        trace
+    }
+}
+impl std::fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InFunction {
+                function,
+                call_pos,
+                error,
+                ..
+            } => {
+                write!(f, "{error}\n    in fn {function} called from {call_pos}")
+            }
+            Self::ExpectedValue { pos, .. } => {
+                write!(f, "Runtime: Expected a value at {pos}.")
+            }
+            Self::UndefinedVariable {
+                id,
+                pos,
+                suggestions,
+                ..
+            } => match suggestions.as_slice() {
+                [] => write!(f, "Runtime: Undefined variable '{id}' at {pos}."),
+                [name] => write!(
+                    f,
+                    "Runtime: Undefined variable '{id}' at {pos}, did you mean '{name}'?"
+                ),
+                names => write!(
+                    f,
+                    "Runtime: Undefined variable '{id}' at {pos}, did you mean one of '{}'?",
+                    names.join("', '")
+                ),
+            },
+            Self::Redeclared { id, pos, .. } => {
+                write!(
+                    f,
+                    "Runtime: '{id}' is already declared in this scope at {pos}."
+                )
+            }
+            Self::AssignImmutable { id, kind, pos, .. } => {
+                write!(
+                    f,
+                    "Runtime: Can not assign to '{id}' declared with {kind} at {pos}."
+                )
+            }
+            Self::VariableTypeMismatch {
+                id,
+                expected,
+                found,
+                pos,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Runtime: '{id}' has type {expected} but was given {found} at {pos}."
+                )
+            }
+            Self::ConditionNotBoolean { found, pos, .. } => {
+                write!(
+                    f,
+                    "Runtime: Condition must be a bool but was {found} at {pos}."
+                )
+            }
+            Self::RangeBounds {
+                start, end, pos, ..
+            } => {
+                write!(
+                    f,
+                    "Runtime: A range must start and end with integers of the same type, but has {start} and {end} at {pos}."
+                )
+            }
+            Self::RangeStepType { found, pos, .. } => {
+                write!(
+                    f,
+                    "Runtime: The step of a range must be an i64, but was {found} at {pos}."
+                )
+            }
+            Self::RangeStepZero { pos, .. } => {
+                write!(f, "Runtime: The step of a range can not be 0 at {pos}.")
+            }
+            Self::NotIterable { found, pos, .. } => {
+                write!(
+                    f,
+                    "Runtime: Can not loop over {found} at {pos}, only over ranges."
+                )
+            }
+            Self::NotCallable { id, found, pos, .. } => {
+                write!(
+                    f,
+                    "Runtime: Can not call '{id}' at {pos}, it is {found} and not a function."
+                )
+            }
+            Self::ArgumentCount {
+                function,
+                expected,
+                found,
+                pos,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Runtime: '{function}' takes {expected} arguments but was given {found} at {pos}."
+                )
+            }
+            Self::ReturnTypeMismatch {
+                function,
+                expected,
+                found: Some(found),
+                pos,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Runtime: '{function}' returns {expected} but returned {found} at {pos}."
+                )
+            }
+            Self::ReturnTypeMismatch {
+                function,
+                expected,
+                found: None,
+                pos,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Runtime: '{function}' returns {expected} but ended without a value at {pos}."
+                )
+            }
+            Self::CallDepth {
+                function,
+                limit,
+                pos,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Runtime: Calling '{function}' at {pos} goes deeper than {limit} calls."
+                )
+            }
+            Self::NestingDepth { limit, pos, .. } => {
+                write!(
+                    f,
+                    "Runtime: Running the expression at {pos} goes deeper than {limit} levels."
+                )
+            }
+            Self::UnaryTypeMismatch { op, ty, pos, .. } => {
+                write!(f, "Runtime: Can not apply unary '{op}' to {ty} at {pos}.")
+            }
+            Self::BinaryTypeMismatch {
+                op, lhs, rhs, pos, ..
+            } => {
+                write!(
+                    f,
+                    "Runtime: Can not apply '{op}' to {lhs} and {rhs} at {pos}."
+                )
+            }
+            Self::Overflow { ty, pos, .. } => {
+                write!(f, "Runtime: Arithmetic overflow of {ty} at {pos}.")
+            }
+            Self::DivideByZero { pos, .. } => {
+                write!(f, "Runtime: Divide by zero at {pos}.")
+            }
+            Self::ShiftOutOfRange {
+                amount, ty, pos, ..
+            } => {
+                write!(
+                    f,
+                    "Runtime: Can not shift {ty} by {amount} at {pos}, the amount must be at least 0 and less than the bits in {ty}."
+                )
+            }
+        }
+    }
+}
+impl std::error::Error for RuntimeError {}
+
+/// Default of Interpreter::max_call_depth.
+pub const MAX_CALL_DEPTH: usize = 200;
+/// Default of Interpreter::max_depth.
+pub const MAX_DEPTH: usize = 1500;
+
+/// Why run_tree stopped before the end of a node.
+#[derive(Debug)]
+enum Unwind {
+    Error(RuntimeError),
+    /// `ret`, goes up to the script or function.
+    Return(Option<Typing>),
+    /// Goes up to the loop with the label, or the innermost loop without one.
+    Break(Option<Ident>, Option<Typing>),
+    Continue(Option<Ident>),
+}
+impl Unwind {
+    /// Whether a break or continue with label ends at a loop with loop_label.
+    fn targets(label: &Option<Ident>, loop_label: &Option<Ident>) -> bool {
+        label.is_none() || label == loop_label
+    }
+}
+impl From<RuntimeError> for Unwind {
+    fn from(err: RuntimeError) -> Self {
+        Self::Error(err)
+    }
 }
 
 #[derive(Debug)]
 pub struct Interpreter {
     pub ast: PosAst,
     pub env: Environment,
+    /// Calls that can be inside of each other before CallDepth.
+    pub max_call_depth: usize,
+    /// Nodes that can run inside of each other before NestingDepth, across calls.
+    /// Every level uses up to a few KB of native stack, lower it when running on a small thread.
+    pub max_depth: usize,
 }
 impl Interpreter {
     pub fn new(ast: PosAst) -> Self {
+        Self::new_with_environment(ast, Environment::new())
+    }
+
+    pub fn new_with_environment(ast: PosAst, envir: Environment) -> Self {
         Self {
             ast,
-            env: Environment::new(),
+            env: envir,
+            max_call_depth: MAX_CALL_DEPTH,
+            max_depth: MAX_DEPTH,
         }
     }
 
-    pub fn new_with_environment(ast: PosAst, envir: Environment) -> Self {
-        Self { ast, env: envir }
-    }
-
-    // TODO: Runs the entire ast.
-    pub fn run(&mut self) -> anyhow::Result<Option<Typing>> {
-        Self::run_tree(&self.ast, &mut self.env)
-    }
-
-    fn run_tree(ast: &PosAst, env: &mut Environment) -> anyhow::Result<Option<Typing>> {
-        let PosAst { ast, pos } = ast;
-        match ast {
-            Ast::Root(ast) => Self::run_tree(ast, env),
-            Ast::UnaryOp(op, ast) => {
-                let expr = Self::run_tree(ast, env)?;
-                match (op, expr) {
-                    (UnaryOperation::Negate, Some(t)) => match t {
-                        Typing::Int64(v) => Ok(Some(Typing::Int64(-v))),
-                        _ => panic!(),
-                    },
-                    (UnaryOperation::BooleanNot, ..) => todo!(),
-                    (UnaryOperation::BitwiseNot, ..) => todo!(),
-                    (op, ..) => panic!(),
+    /// Runs the entire ast, `ret` ends the script with its value.
+    pub fn run(&mut self) -> Result<Option<Typing>, RuntimeError> {
+        let mut runner = Runner {
+            env: &mut self.env,
+            max_call_depth: self.max_call_depth,
+            max_depth: self.max_depth,
+            depth: 0,
+        };
+        match runner.run_tree(&self.ast) {
+            Ok(val) | Err(Unwind::Return(val)) => Ok(val),
+            Err(Unwind::Error(err)) => Err(err),
+            Err(Unwind::Break(..) | Unwind::Continue(_)) => {
+                unreachable!("The parser checks that break and continue are inside of a loop.")
+            }
+        }
+    }
+}
+
+/// Runs the nodes of one Interpreter::run, with the limits of the Interpreter.
+struct Runner<'a> {
+    env: &'a mut Environment,
+    max_call_depth: usize,
+    max_depth: usize,
+    /// Nodes that are running inside of each other.
+    depth: usize,
+}
+impl Runner<'_> {
+    /// Every node runs through here, so counting the depth here bounds the native stack.
+    /// It only picks the function for a node, the functions with large frames are never inlined,
+    /// so their locals are only on the stack for the nodes that use them.
+    fn run_tree(&mut self, ast: &PosAst) -> Result<Option<Typing>, Unwind> {
+        if self.depth >= self.max_depth {
+            return Err(self.nesting_depth(ast.pos, ast.span));
+        }
+        self.depth += 1;
+        let PosAst { ast, pos, span } = ast;
+        let val = match ast {
+            Ast::Root(stmts) => self.run_statements(stmts),
+            Ast::Block(stmts) => self.run_block(stmts),
+            Ast::Statement(ast) => self.run_statement(ast),
+            Ast::If(cond, then, otherwise) => self.run_if(cond, then, otherwise.as_deref()),
+            Ast::Guard(conds, otherwise) => self.run_guard(conds, otherwise),
+            Ast::Return(ast) => self.run_return(ast.as_deref()),
+            Ast::While(label, cond, body) => self.run_while(label, cond, body),
+            Ast::For(label, id, iter, body) => self.run_for(label, id, iter, body),
+            Ast::Range(inclusive, start, end, step) => {
+                self.run_range(*inclusive, start, end, step.as_deref(), *pos, *span)
+            }
+            Ast::Break(label, ast) => self.run_break(label, ast.as_deref()),
+            Ast::Continue(label) => Self::run_continue(label),
+            // Declared by run_statements before the block runs.
+            Ast::Function(_) => Ok(None),
+            Ast::Call(id, args) => self.run_call(id, args, *pos, *span),
+            Ast::UnaryOp(op, ast) => self.run_unary(*op, ast, *pos, *span),
+            Ast::BinOp(op, a1, a2) => self.run_binary(*op, a1, a2, *pos, *span),
+            Ast::Declaration(kind, id, ty, ast) => {
+                self.run_declaration(*kind, id, *ty, ast, *pos, *span)
+            }
+            Ast::Assignment(id, ast) => self.run_assignment(id, ast, *pos, *span),
+            Ast::Value(val) => self.run_literal(val, *pos, *span),
+        };
+        self.depth -= 1;
+        val
+    }
+
+    fn run_block(&mut self, stmts: &[PosAst]) -> Result<Option<Typing>, Unwind> {
+        self.env.push();
+        let val = self.run_statements(stmts);
+        self.env.pop();
+        val
+    }
+
+    fn run_statement(&mut self, ast: &PosAst) -> Result<Option<Typing>, Unwind> {
+        self.run_tree(ast)?;
+        Ok(None)
+    }
+
+    // TODO: Check that both branches have the same type once there is a type checker.
+    fn run_if(
+        &mut self,
+        cond: &PosAst,
+        then: &PosAst,
+        otherwise: Option<&PosAst>,
+    ) -> Result<Option<Typing>, Unwind> {
+        match (self.run_condition(cond)?, otherwise) {
+            (true, Some(_)) => self.run_tree(then),
+            (true, None) => self.run_statement(then),
+            (false, Some(otherwise)) => self.run_tree(otherwise),
+            (false, None) => Ok(None),
+        }
+    }
+
+    #[inline(never)]
+    fn run_return(&mut self, ast: Option<&PosAst>) -> Result<Option<Typing>, Unwind> {
+        let val = match ast {
+            Some(ast) => Some(self.run_value(ast)?),
+            None => None,
+        };
+        Err(Unwind::Return(val))
+    }
+
+    #[inline(never)]
+    fn run_while(
+        &mut self,
+        label: &Option<Ident>,
+        cond: &PosAst,
+        body: &PosAst,
+    ) -> Result<Option<Typing>, Unwind> {
+        while self.run_condition(cond)? {
+            if let ControlFlow::Break(val) = self.run_iteration(label, body)? {
+                return Ok(val);
+            }
+        }
+        Ok(None)
+    }
+
+    #[inline(never)]
+    fn run_break(
+        &mut self,
+        label: &Option<Ident>,
+        ast: Option<&PosAst>,
+    ) -> Result<Option<Typing>, Unwind> {
+        let val = match ast {
+            Some(ast) => Some(self.run_value(ast)?),
+            None => None,
+        };
+        Err(Unwind::Break(label.clone(), val))
+    }
+
+    #[inline(never)]
+    fn run_continue(label: &Option<Ident>) -> Result<Option<Typing>, Unwind> {
+        Err(Unwind::Continue(label.clone()))
+    }
+
+    #[inline(never)]
+    fn run_unary(
+        &mut self,
+        op: UnaryOperation,
+        ast: &PosAst,
+        pos: Pos,
+        span: Span,
+    ) -> Result<Option<Typing>, Unwind> {
+        let val = self.run_value(ast)?;
+        Ok(Some(unary_ops::unary_op(op, val, pos, span)?))
+    }
+
+    /// Variables are looked up and strings are filled, other values are used as they are.
+    #[inline(never)]
+    fn run_literal(&self, val: &Typing, pos: Pos, span: Span) -> Result<Option<Typing>, Unwind> {
+        match val {
+            Typing::String(string) if !string.is_filled() => {
+                Ok(Some(self.fill_string(string, pos, span)?))
+            }
+            Typing::Ident(id) => match self.env.get(id) {
+                Some(val) => Ok(Some(val.clone())),
+                None => Err(suggest::undefined(id, pos, span, self.env).into()),
+            },
+            val => Ok(Some(val.clone())),
+        }
+    }
+
+    /// Declarations stay in the current frame, the else block always diverges.
+    #[inline(never)]
+    fn run_guard(
+        &mut self,
+        conds: &[PosAst],
+        otherwise: &PosAst,
+    ) -> Result<Option<Typing>, Unwind> {
+        for cond in conds {
+            if let Ast::Declaration(..) = cond.ast {
+                self.run_tree(cond)?;
+            }
+            else if !self.run_condition(cond)? {
+                self.run_tree(otherwise)?;
+                unreachable!("The parser checks that the else of guard diverges.");
+            }
+        }
+        Ok(None)
+    }
+
+    /// Declares id in a new frame for every value of the range.
+    #[inline(never)]
+    fn run_for(
+        &mut self,
+        label: &Option<Ident>,
+        id: &Ident,
+        iter: &PosAst,
+        body: &PosAst,
+    ) -> Result<Option<Typing>, Unwind> {
+        let range = match self.run_value(iter)? {
+            Typing::Range(range) => range,
+            val => {
+                return Err(RuntimeError::NotIterable {
+                    found: val.raw(),
+                    pos: iter.pos,
+                    span: iter.span,
                 }
+                .into())
+            }
+        };
+        for val in range.iter() {
+            self.env.push();
+            self.env.declare(id.clone(), DeclarationKind::Let, val);
+            let flow = self.run_iteration(label, body);
+            self.env.pop();
+            if let ControlFlow::Break(val) = flow? {
+                return Ok(val);
             }
-            Ast::BinOp(op, a1, a2) => {
-                let expr1 = Self::run_tree(a1, env)?;
-                let expr2 = Self::run_tree(a2, env)?;
-                match (op, expr1, expr2) {
-                    (BinaryOperation::Add, Some(v1), Some(v2)) => match (v1, v2) {
-                        (Typing::Int64(i1), Typing::Int64(i2)) => Ok(Some(Typing::Int64(i1 + i2))),
-                        _ => panic!(),
-                    },
-                    (op, ..) => panic!(),
+        }
+        Ok(None)
+    }
+
+    #[inline(never)]
+    fn run_range(
+        &mut self,
+        inclusive: bool,
+        start: &PosAst,
+        end: &PosAst,
+        step: Option<&PosAst>,
+        pos: Pos,
+        span: Span,
+    ) -> Result<Option<Typing>, Unwind> {
+        let (start_val, end_val) = (self.run_value(start)?, self.run_value(end)?);
+        let step = match step {
+            Some(step) => match self.run_value(step)? {
+                Typing::Int64(0) => {
+                    return Err(RuntimeError::RangeStepZero {
+                        pos: step.pos,
+                        span: step.span,
+                    }
+                    .into())
                 }
+                Typing::Int64(v) => v,
+                val => {
+                    return Err(RuntimeError::RangeStepType {
+                        found: val.raw(),
+                        pos: step.pos,
+                        span: step.span,
+                    }
+                    .into())
+                }
+            },
+            None => 1,
+        };
+        let range = Range::new(&start_val, &end_val, inclusive, step).ok_or_else(|| {
+            RuntimeError::RangeBounds {
+                start: start_val.raw(),
+                end: end_val.raw(),
+                pos,
+                span,
+            }
+        })?;
+        Ok(Some(Typing::Range(range.into())))
+    }
+
+    fn run_binary(
+        &mut self,
+        op: BinaryOperation,
+        a1: &PosAst,
+        a2: &PosAst,
+        pos: Pos,
+        span: Span,
+    ) -> Result<Option<Typing>, Unwind> {
+        let expr1 = self.run_tree(a1)?;
+        // Short circuit
+        match (op, &expr1) {
+            (BinaryOperation::BooleanAnd, Some(Typing::Boolean(false))) => {
+                return Ok(Some(Typing::Boolean(false)))
+            }
+            (BinaryOperation::BooleanOr, Some(Typing::Boolean(true))) => {
+                return Ok(Some(Typing::Boolean(true)))
+            }
+            _ => {}
+        }
+        let expr2 = self.run_tree(a2)?;
+        Self::binary_values(op, expr1, expr2, a1, a2, pos, span)
+    }
+
+    #[inline(never)]
+    fn binary_values(
+        op: BinaryOperation,
+        expr1: Option<Typing>,
+        expr2: Option<Typing>,
+        a1: &PosAst,
+        a2: &PosAst,
+        pos: Pos,
+        span: Span,
+    ) -> Result<Option<Typing>, Unwind> {
+        match (expr1, expr2) {
+            (Some(v1), Some(v2)) => Ok(Some(binary_ops::binary_op(op, v1, v2, pos, span)?)),
+            (None, _) => Err(Self::expected_value(a1).into()),
+            (_, None) => Err(Self::expected_value(a2).into()),
+        }
+    }
+
+    /// Declares the functions of a block, they are siblings of each other.
+    #[inline(never)]
+    fn run_functions(&mut self, stmts: &[PosAst]) -> Result<(), RuntimeError> {
+        let functions: Vec<_> = stmts.iter().filter_map(Self::function_of).collect();
+        if functions.is_empty() {
+            return Ok(());
+        }
+        let siblings: Rc<[Rc<Function>]> = functions.iter().map(|(f, ..)| Rc::clone(f)).collect();
+        for (function, pos, span) in functions {
+            if self.env.contains_offset(&function.name, 0) {
+                return Err(RuntimeError::Redeclared {
+                    id: function.name.clone(),
+                    pos,
+                    span,
+                });
             }
-            Ast::Value(val) => Ok(Some(val.clone())),
-            _ => todo!(),
+            self.declare_function(function, &siblings);
+        }
+        Ok(())
+    }
+
+    fn declare_function(&mut self, function: &Rc<Function>, siblings: &Rc<[Rc<Function>]>) {
+        let val = Typing::Function(FunctionValue {
+            function: Rc::clone(function),
+            siblings: Rc::clone(siblings),
+        });
+        self.env
+            .declare(function.name.clone(), DeclarationKind::Let, val);
+    }
+
+    #[inline(never)]
+    fn run_declaration(
+        &mut self,
+        kind: DeclarationKind,
+        id: &Ident,
+        ty: Option<RawTyping>,
+        ast: &PosAst,
+        pos: Pos,
+        span: Span,
+    ) -> Result<Option<Typing>, Unwind> {
+        if self.env.contains_offset(id, 0) {
+            return Err(RuntimeError::Redeclared {
+                id: id.clone(),
+                pos,
+                span,
+            }
+            .into());
+        }
+        let val = self.run_value(ast)?;
+        if let Some(ty) = ty {
+            Self::check_type(id, ty, &val, pos, span)?;
+        }
+        self.env.declare(id.clone(), kind, val);
+        Ok(None)
+    }
+
+    #[inline(never)]
+    fn run_assignment(
+        &mut self,
+        id: &Ident,
+        ast: &PosAst,
+        pos: Pos,
+        span: Span,
+    ) -> Result<Option<Typing>, Unwind> {
+        let val = self.run_value(ast)?;
+        let err = match self.env.set(id, val.clone()) {
+            Ok(_) => return Ok(Some(val)),
+            Err(SetError::Undefined) => suggest::undefined(id, pos, span, self.env),
+            Err(SetError::Immutable(kind)) => RuntimeError::AssignImmutable {
+                id: id.clone(),
+                kind,
+                pos,
+                span,
+            },
+            Err(SetError::TypeMismatch { expected, found }) => RuntimeError::VariableTypeMismatch {
+                id: id.clone(),
+                expected,
+                found,
+                pos,
+                span,
+            },
+        };
+        Err(err.into())
+    }
+
+    /// Value of the last statement, statements ending with `;` have none.
+    /// Functions are declared first, so they can be called before their declaration.
+    fn run_statements(&mut self, stmts: &[PosAst]) -> Result<Option<Typing>, Unwind> {
+        self.run_functions(stmts)?;
+        let mut val = None;
+        for stmt in stmts {
+            val = self.run_tree(stmt)?;
+        }
+        Ok(val)
+    }
+
+    fn function_of(stmt: &PosAst) -> Option<(&Rc<Function>, Pos, Span)> {
+        match &stmt.ast {
+            Ast::Function(function) => Some((function, stmt.pos, stmt.span)),
+            Ast::Statement(a) => Self::function_of(a),
+            _ => None,
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn nesting_depth(&self, pos: Pos, span: Span) -> Unwind {
+        RuntimeError::NestingDepth {
+            limit: self.max_depth,
+            pos,
+            span,
+        }
+        .into()
+    }
+
+    /// Arguments run in the frame of the caller, the body in a new call frame.
+    /// The checks are in other functions, so recursion through here uses little native stack.
+    fn run_call(
+        &mut self,
+        id: &Ident,
+        args: &[PosAst],
+        pos: Pos,
+        span: Span,
+    ) -> Result<Option<Typing>, Unwind> {
+        let function = self.call_start(id, args, pos, span)?;
+        let result = self.run_tree(&function.body);
+        self.env.pop_call();
+        Self::call_result(id, &function, result, pos, span)
+    }
+
+    #[inline(never)]
+    fn call_function(
+        &self,
+        id: &Ident,
+        args: usize,
+        pos: Pos,
+        span: Span,
+    ) -> Result<FunctionValue, RuntimeError> {
+        let function = match self.env.get(id) {
+            Some(Typing::Function(function)) => function.clone(),
+            Some(val) => {
+                return Err(RuntimeError::NotCallable {
+                    id: id.clone(),
+                    found: val.raw(),
+                    pos,
+                    span,
+                })
+            }
+            None => return Err(suggest::undefined(id, pos, span, self.env)),
+        };
+        if args != function.function.params.len() {
+            return Err(RuntimeError::ArgumentCount {
+                function: id.clone(),
+                expected: function.function.params.len(),
+                found: args,
+                pos,
+                span,
+            });
+        }
+        Ok(function)
+    }
+
+    /// Runs the arguments in the frame of the caller and checks their types.
+    #[inline(never)]
+    fn call_args(&mut self, function: &Function, args: &[PosAst]) -> Result<Vec<Typing>, Unwind> {
+        let mut vals = Vec::with_capacity(args.len());
+        for ((param, ty), arg) in function.params.iter().zip(args) {
+            let val = self.run_value(arg)?;
+            Self::check_type(param, *ty, &val, arg.pos, arg.span)?;
+            vals.push(val);
+        }
+        Ok(vals)
+    }
+
+    /// Only its small frame is on the stack while the arguments run, call_push has the large one.
+    #[inline(never)]
+    fn call_start(
+        &mut self,
+        id: &Ident,
+        args: &[PosAst],
+        pos: Pos,
+        span: Span,
+    ) -> Result<Rc<Function>, Unwind> {
+        let function = self.call_function(id, args.len(), pos, span)?;
+        let vals = self.call_args(&function.function, args)?;
+        self.call_push(id, function, vals, pos, span)
+    }
+
+    /// Pushes the call frame with the params and the siblings of the function declared.
+    #[inline(never)]
+    fn call_push(
+        &mut self,
+        id: &Ident,
+        function: FunctionValue,
+        vals: Vec<Typing>,
+        pos: Pos,
+        span: Span,
+    ) -> Result<Rc<Function>, Unwind> {
+        let FunctionValue { function, siblings } = function;
+        if self.env.call_depth() >= self.max_call_depth {
+            return Err(RuntimeError::CallDepth {
+                function: id.clone(),
+                limit: self.max_call_depth,
+                pos,
+                span,
+            }
+            .into());
+        }
+
+        self.env.push_call();
+        // Lets the function call itself and its siblings, even when they are declared in a frame
+        // the call can not see.
+        for sibling in siblings.iter() {
+            self.declare_function(sibling, &siblings);
+        }
+        for ((param, _), val) in function.params.iter().zip(vals) {
+            self.env.declare(param.clone(), DeclarationKind::Let, val);
+        }
+        Ok(function)
+    }
+
+    #[inline(never)]
+    fn call_result(
+        id: &Ident,
+        function: &Function,
+        result: Result<Option<Typing>, Unwind>,
+        pos: Pos,
+        span: Span,
+    ) -> Result<Option<Typing>, Unwind> {
+        let val = match result {
+            Ok(val) | Err(Unwind::Return(val)) => val,
+            Err(Unwind::Error(
+                err @ (RuntimeError::CallDepth { .. } | RuntimeError::NestingDepth { .. }),
+            )) => return Err(err.into()),
+            Err(Unwind::Error(err)) => return Err(err.in_function(id.clone(), pos, span).into()),
+            Err(Unwind::Break(..) | Unwind::Continue(_)) => {
+                unreachable!("The parser checks that break and continue are inside of a loop.")
+            }
+        };
+        match (function.ret, val) {
+            (None, _) => Ok(None),
+            (Some(ty), Some(val)) if val.raw() == ty => Ok(Some(val)),
+            (Some(expected), found) => Err(RuntimeError::ReturnTypeMismatch {
+                function: id.clone(),
+                expected,
+                found: found.as_ref().map(Typing::raw),
+                pos,
+                span,
+            }
+            .into()),
+        }
+    }
+
+    /// Runs the body of a loop once, Break when the loop should end with the value.
+    fn run_iteration(
+        &mut self,
+        label: &Option<Ident>,
+        body: &PosAst,
+    ) -> Result<ControlFlow<Option<Typing>>, Unwind> {
+        match self.run_tree(body) {
+            Ok(_) => Ok(ControlFlow::Continue(())),
+            Err(Unwind::Break(l, val)) if Unwind::targets(&l, label) => Ok(ControlFlow::Break(val)),
+            Err(Unwind::Continue(l)) if Unwind::targets(&l, label) => Ok(ControlFlow::Continue(())),
+            Err(unwind) => Err(unwind),
+        }
+    }
+
+    /// Inserts the values of the variables in string.
+    #[inline(never)]
+    fn fill_string(&self, string: &ChoppedString, pos: Pos, span: Span) -> Result<Typing, Unwind> {
+        let string = string.fill_with(|id| match self.env.get(id) {
+            Some(val) => Ok(val.to_string()),
+            None => Err(suggest::undefined(id, pos, span, self.env)),
+        })?;
+        Ok(Typing::String(string.into()))
+    }
+
+    /// Runs an expression that must have a value.
+    fn run_value(&mut self, ast: &PosAst) -> Result<Typing, Unwind> {
+        self.run_tree(ast)?
+            .ok_or_else(|| Self::expected_value(ast).into())
+    }
+
+    fn expected_value(ast: &PosAst) -> RuntimeError {
+        RuntimeError::ExpectedValue {
+            pos: ast.pos,
+            span: ast.span,
+        }
+    }
+
+    /// Runs a condition, which must be a bool.
+    fn run_condition(&mut self, ast: &PosAst) -> Result<bool, Unwind> {
+        match self.run_value(ast)? {
+            Typing::Boolean(b) => Ok(b),
+            val => Err(RuntimeError::ConditionNotBoolean {
+                found: val.raw(),
+                pos: ast.pos,
+                span: ast.span,
+            }
+            .into()),
+        }
+    }
+
+    fn check_type(
+        id: &Ident,
+        expected: RawTyping,
+        val: &Typing,
+        pos: Pos,
+        span: Span,
+    ) -> Result<(), RuntimeError> {
+        if val.raw() == expected {
+            Ok(())
+        }
+        else {
+            Err(RuntimeError::VariableTypeMismatch {
+                id: id.clone(),
+                expected,
+                found: val.raw(),
+                pos,
+                span,
+            })
         }
     }
 }
@@ -0,0 +1,61 @@
+use crate::{
+    data::{Environment, Ident},
+    interpreter::RuntimeError,
+    Pos, Span,
+};
+
+/// At most this many names are suggested.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Undefined variable error, suggesting visible names that are close to id.
+pub fn undefined(id: &Ident, pos: Pos, span: Span, env: &Environment) -> RuntimeError {
+    RuntimeError::UndefinedVariable {
+        id: id.clone(),
+        pos,
+        span,
+        suggestions: suggest(id, env.names()),
+    }
+}
+
+/// Names within a third of the length of id in edits, closest first.
+pub fn suggest<'a>(id: &str, names: impl IntoIterator<Item = &'a Ident>) -> Vec<Ident> {
+    let max = (id.chars().count() / 3).max(1);
+    let mut close: Vec<(usize, &Ident)> = names
+        .into_iter()
+        .filter(|name| name.as_str() != id)
+        .map(|name| (distance(id, name), name))
+        .filter(|(dist, _)| *dist <= max)
+        .collect();
+    close.sort();
+    close.dedup();
+    close
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name.clone())
+        .collect()
+}
+
+/// Edit distance in chars, swapping two chars next to each other is one edit.
+fn distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut dist = (rows[i - 1][j - 1] + cost)
+                .min(rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist = dist.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = dist;
+        }
+    }
+
+    rows[a.len()][b.len()]
+}
@@ -1 +1,27 @@
+use crate::{
+    data::{Typing, UnaryOperation},
+    interpreter::RuntimeError,
+    Pos, Span,
+};
 
+pub fn unary_op(
+    op: UnaryOperation,
+    val: Typing,
+    pos: Pos,
+    span: Span,
+) -> Result<Typing, RuntimeError> {
+    let ty = val.raw();
+    let ret = match (op, val) {
+        (UnaryOperation::Negate, Typing::Int64(v)) => match v.checked_neg() {
+            Some(v) => Typing::Int64(v),
+            None => return Err(RuntimeError::Overflow { ty, pos, span }),
+        },
+        (UnaryOperation::Negate, Typing::Float64(v)) => Typing::Float64(-v),
+        (UnaryOperation::BooleanNot, Typing::Boolean(v)) => Typing::Boolean(!v),
+        (UnaryOperation::BitwiseNot, Typing::Int64(v)) => Typing::Int64(!v),
+        (UnaryOperation::BitwiseNot, Typing::UInt64(v)) => Typing::UInt64(!v),
+        (UnaryOperation::BitwiseNot, Typing::UInt8(v)) => Typing::UInt8(!v),
+        _ => return Err(RuntimeError::UnaryTypeMismatch { op, ty, pos, span }),
+    };
+    Ok(ret)
+}
@@ -1,6 +1,6 @@
 use crate::{
-    data::{Ident, PosToken, Token},
-    Pos,
+    data::{ChoppedString, Ident, PosToken, RawTyping, Token},
+    Pos, Span,
 };
 use std::{collections::VecDeque, iter::Fuse, str::Chars};
 
@@ -8,6 +8,7 @@ use std::{collections::VecDeque, iter::Fuse, str::Chars};
 struct Lexer<'a> {
     iter: Fuse<Chars<'a>>,
     tmp: VecDeque<char>,
+    /// Byte offset of the next char.
     index: usize,
     line: usize,
     col: usize,
@@ -59,13 +60,13 @@ impl<'a> Lexer<'a> {
             _ => a,
         };
 
-        match &item {
-            Some(c) => match c {
+        if let Some(c) = &item {
+            self.index += c.len_utf8();
+            match c {
                 '\0' => {}
                 '\n' => self.newline(),
                 _ => self.increment(1),
-            },
-            None => {}
+            }
         }
 
         item
@@ -81,13 +82,16 @@ impl<'a> Lexer<'a> {
         Pos::new(self.line, self.col)
     }
 
+    /// Span from start to the next char.
+    const fn span_from(&self, start: usize) -> Span {
+        Span::new(start, self.index)
+    }
+
     fn increment(&mut self, i: usize) {
-        self.index += i;
         self.col += i;
     }
 
     fn newline(&mut self) {
-        self.index += 1;
         self.line += 1;
         self.col = 1;
     }
@@ -119,20 +123,63 @@ impl PunchToken {
     }
 
     fn add_i(&mut self, lexer: &mut Lexer, token: Token, consume: usize) {
-        self.tokens.push(PosToken::new(token, lexer.current_pos()));
+        let (pos, start) = (lexer.current_pos(), lexer.index);
         lexer.skip_i(consume);
+        self.tokens
+            .push(PosToken::new(token, pos, lexer.span_from(start)));
     }
 }
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum LexerError {
+    UnexpectedChar { c: char, pos: Pos },
     DecimalBadToken { c: char, pos: Pos },
     DecimalMoreThanOnePeriod { pos: Pos },
     MacroBadIdent { c: char, pos: Pos },
+    BlockCommentUnterminated { pos: Pos },
+    NumberBadDigit { c: char, radix: u32, pos: Pos },
+    NumberMissingDigits { radix: u32, pos: Pos },
+    NumberOutOfRange { radix: u32, pos: Pos },
+    NumberBadSuffix { suffix: String, pos: Pos },
+    CharEmpty { pos: Pos },
+    CharMultiple { pos: Pos },
+    CharUnterminated { pos: Pos },
+    EscapeBad { c: char, pos: Pos },
+    EscapeBadUnicode { pos: Pos },
+    StringMissingQuote { pos: Pos },
+    StringUnterminated { pos: Pos },
+    StringBadInterpolation { pos: Pos },
+}
+impl LexerError {
+    #[must_use]
+    pub const fn pos(&self) -> Pos {
+        match self {
+            Self::UnexpectedChar { pos, .. }
+            | Self::DecimalBadToken { pos, .. }
+            | Self::DecimalMoreThanOnePeriod { pos }
+            | Self::MacroBadIdent { pos, .. }
+            | Self::BlockCommentUnterminated { pos }
+            | Self::NumberBadDigit { pos, .. }
+            | Self::NumberMissingDigits { pos, .. }
+            | Self::NumberOutOfRange { pos, .. }
+            | Self::NumberBadSuffix { pos, .. }
+            | Self::CharEmpty { pos }
+            | Self::CharMultiple { pos }
+            | Self::CharUnterminated { pos }
+            | Self::EscapeBad { pos, .. }
+            | Self::EscapeBadUnicode { pos }
+            | Self::StringMissingQuote { pos }
+            | Self::StringUnterminated { pos }
+            | Self::StringBadInterpolation { pos } => *pos,
+        }
+    }
 }
 impl std::fmt::Display for LexerError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Self::UnexpectedChar { c, pos } => {
+                write!(f, "Lexer: Unexpected character {c:?} at {pos}.")
+            }
             Self::DecimalBadToken { c, pos } => {
                 write!(f, "Lexer: Could not lex decimal, bad token '{c}' at {pos}.")
             }
@@ -145,6 +192,63 @@ impl std::fmt::Display for LexerError {
             Self::MacroBadIdent { c, pos } => {
                 write!(f, "Lexer: Non _ or ascii character '{c}' at {pos}.")
             }
+            Self::BlockCommentUnterminated { pos } => {
+                write!(f, "Lexer: Block comment starting at {pos} is never closed.")
+            }
+            Self::NumberBadDigit { c, radix, pos } => {
+                write!(
+                    f,
+                    "Lexer: Invalid digit '{c}' for a base {radix} number at {pos}."
+                )
+            }
+            Self::NumberMissingDigits { radix, pos } => {
+                write!(f, "Lexer: Base {radix} number at {pos} has no digits.")
+            }
+            Self::NumberOutOfRange { radix, pos } => {
+                write!(
+                    f,
+                    "Lexer: Base {radix} number at {pos} does not fit in 64 bits."
+                )
+            }
+            Self::NumberBadSuffix { suffix, pos } => {
+                write!(
+                    f,
+                    "Lexer: Unknown number suffix '{suffix}' at {pos}, expected one of u8, u64, i64 or f64."
+                )
+            }
+            Self::CharEmpty { pos } => {
+                write!(f, "Lexer: Empty character literal at {pos}.")
+            }
+            Self::CharMultiple { pos } => {
+                write!(
+                    f,
+                    "Lexer: Character literal at {pos} has more than one character."
+                )
+            }
+            Self::CharUnterminated { pos } => {
+                write!(f, "Lexer: Character literal at {pos} is never closed.")
+            }
+            Self::EscapeBad { c, pos } => {
+                write!(f, "Lexer: Unknown escape '\\{c}' at {pos}.")
+            }
+            Self::EscapeBadUnicode { pos } => {
+                write!(
+                    f,
+                    "Lexer: Invalid unicode escape at {pos}, expected \\u{{1-6 hex digits}}."
+                )
+            }
+            Self::StringMissingQuote { pos } => {
+                write!(f, "Lexer: Expected '\"' to start string at {pos}.")
+            }
+            Self::StringUnterminated { pos } => {
+                write!(f, "Lexer: String starting at {pos} is never closed.")
+            }
+            Self::StringBadInterpolation { pos } => {
+                write!(
+                    f,
+                    "Lexer: Invalid interpolation at {pos}, expected {{ident}}, use \\{{ for a literal {{."
+                )
+            }
         }
     }
 }
@@ -169,8 +273,12 @@ pub fn lex(contents: &str) -> anyhow::Result<Vec<PosToken>> {
         );
         match (c1, c2, c3) {
             // Comments
-            ('/', '/', _) => todo!(), // Single line
-            ('/', '*', _) => todo!(), // Multi line
+            ('/', '/', _) => {
+                if let Some(token) = get_line_comment(lexer) {
+                    tokens.tokens.push(token);
+                }
+            }
+            ('/', '*', _) => get_block_comment(lexer)?,
 
             ('.', '.', '<') => tokens.add3(lexer, Token::RangeExclusive),
             ('.', '.', '=') => tokens.add3(lexer, Token::RangeInclusive),
@@ -182,10 +290,10 @@ pub fn lex(contents: &str) -> anyhow::Result<Vec<PosToken>> {
             ('%', '=', _) => tokens.add2(lexer, Token::AssignMathModulus),
             ('+', '=', _) => tokens.add2(lexer, Token::AssignMathAdd),
             ('-', '=', _) => tokens.add2(lexer, Token::AssignMathSubtract),
-            ('~', '=', _) => tokens.add2(lexer, Token::AssignBitNot),
             ('&', '=', _) => tokens.add2(lexer, Token::AssignBitAnd),
             ('^', '=', _) => tokens.add2(lexer, Token::AssignBitXor),
             ('|', '=', _) => tokens.add2(lexer, Token::AssignBitOr),
+            ('-', '>', _) => tokens.add2(lexer, Token::Arrow),
             ('&', '&', _) => tokens.add2(lexer, Token::BoolAnd),
             ('^', '^', _) => tokens.add2(lexer, Token::BoolXor),
             ('|', '|', _) => tokens.add2(lexer, Token::BoolOr),
@@ -220,13 +328,16 @@ pub fn lex(contents: &str) -> anyhow::Result<Vec<PosToken>> {
             (':', _, _) => tokens.add1(lexer, Token::Colon),
             (';', _, _) => tokens.add1(lexer, Token::SemiColon),
 
-            ('0', 'x', _) => todo!(), // Hex
-            ('0', 'o', _) => todo!(), // Octal
-            ('0', 'b', _) => todo!(), // Binary
+            ('0', 'x', _) => tokens.tokens.push(get_radix(lexer, 16)?), // Hex
+            ('0', 'o', _) => tokens.tokens.push(get_radix(lexer, 8)?),  // Octal
+            ('0', 'b', _) => tokens.tokens.push(get_radix(lexer, 2)?),  // Binary
             ('0'..='9', _, _) => tokens.tokens.push(get_decimal(lexer)?), // Decimal
 
-            ('\'', _, _) => todo!(),                            // Char
-            ('"', _, _) | ('#' | 'r', '"' | '#', _) => todo!(), // String
+            ('\'', _, _) if is_label(lexer, tokens.tokens.last()) => {
+                tokens.tokens.push(get_label(lexer))
+            } // Label
+            ('\'', _, _) => tokens.tokens.push(get_char(lexer)?), // Char
+            ('"', _, _) | ('#' | 'r', '"' | '#', _) => tokens.tokens.push(get_string(lexer)?), // String
 
             ('.', _, _) => tokens.add1(lexer, Token::IdentSplit), // Ident Split
             ('#', _, _) => tokens.tokens.push(get_macro(lexer)?), // Macro
@@ -234,17 +345,29 @@ pub fn lex(contents: &str) -> anyhow::Result<Vec<PosToken>> {
 
             // ('', _, _) => tokens.add1(lexer, Token::),
             (whitespace!(), _, _) => lexer.skip_i(1),
-            _ => todo!(),
+            (c, _, _) => {
+                return Err(LexerError::UnexpectedChar {
+                    c,
+                    pos: lexer.current_pos(),
+                }
+                .into());
+            }
         }
     }
 
     match tokens.tokens.last() {
         Some(last) => {
             if !last.token.eq(&Token::Eof) {
-                tokens.tokens.push(PosToken::eof(lexer.current_pos()));
+                tokens.tokens.push(PosToken::eof(
+                    lexer.current_pos(),
+                    lexer.span_from(lexer.index),
+                ));
             }
         }
-        None => tokens.tokens.push(PosToken::eof(lexer.current_pos())),
+        None => tokens.tokens.push(PosToken::eof(
+            lexer.current_pos(),
+            lexer.span_from(lexer.index),
+        )),
     }
 
     Ok(tokens.tokens)
@@ -254,11 +377,49 @@ fn map_opt_char(opt_char: Option<&char>) -> char {
     opt_char.map_or('\0', |c| *c)
 }
 
-fn get_decimal(lexer: &mut Lexer) -> anyhow::Result<PosToken> {
-    let pos = lexer.current_pos();
+/// Skips a line comment, returning a doc comment token for `///` and `//!`.
+fn get_line_comment(lexer: &mut Lexer) -> Option<PosToken> {
+    let (pos, start) = (lexer.current_pos(), lexer.index);
+
+    // `////` is a plain comment, same as rust.
+    let doc = match (lexer.peek_i(2), lexer.peek_i(3)) {
+        (Some('/'), Some('/')) => None,
+        (Some('/'), _) => Some(false),
+        (Some('!'), _) => Some(true),
+        _ => None,
+    };
+    lexer.skip_i(if doc.is_some() { 3 } else { 2 });
+
     let mut builder = String::new();
+    while lexer.within() {
+        match map_opt_char(lexer.peek().as_ref()) {
+            '\n' => break,
+            c => {
+                builder.push(c);
+                lexer.skip_i(1);
+            }
+        }
+    }
 
-    let mut period = false;
+    doc.map(|inner| {
+        let text = builder
+            .strip_prefix(' ')
+            .unwrap_or(&builder)
+            .trim_end()
+            .to_string();
+        if inner {
+            PosToken::new(Token::InnerDocComment(text), pos, lexer.span_from(start))
+        }
+        else {
+            PosToken::new(Token::DocComment(text), pos, lexer.span_from(start))
+        }
+    })
+}
+
+/// Skips a block comment, block comments can be nested.
+fn get_block_comment(lexer: &mut Lexer) -> anyhow::Result<()> {
+    let pos = lexer.current_pos();
+    let mut depth = 0_usize;
 
     while lexer.within() {
         let (c1, c2) = (
@@ -266,11 +427,50 @@ fn get_decimal(lexer: &mut Lexer) -> anyhow::Result<PosToken> {
             map_opt_char(lexer.peek_i(1).as_ref()),
         );
         match (c1, c2) {
-            ('0'..='9', _) => {
+            ('/', '*') => {
+                depth += 1;
+                lexer.skip_i(2);
+            }
+            ('*', '/') => {
+                depth -= 1;
+                lexer.skip_i(2);
+                if depth == 0 {
+                    return Ok(());
+                }
+            }
+            _ => lexer.skip_i(1),
+        }
+    }
+
+    Err(LexerError::BlockCommentUnterminated { pos }.into())
+}
+
+fn get_decimal(lexer: &mut Lexer) -> anyhow::Result<PosToken> {
+    let (pos, start) = (lexer.current_pos(), lexer.index);
+    let mut builder = String::new();
+
+    let mut period = false;
+    let mut exponent = false;
+
+    while lexer.within() {
+        let (c1, c2, c3) = (
+            map_opt_char(lexer.peek().as_ref()),
+            map_opt_char(lexer.peek_i(1).as_ref()),
+            map_opt_char(lexer.peek_i(2).as_ref()),
+        );
+        match (c1, c2, c3) {
+            ('0'..='9', _, _) => {
                 builder.push(c1);
                 lexer.skip_i(1);
             }
-            ('.', '0'..='9') => {
+            ('.', '0'..='9', _) => {
+                if exponent {
+                    return Err(LexerError::DecimalBadToken {
+                        c: c1,
+                        pos: lexer.current_pos(),
+                    }
+                    .into());
+                }
                 if period {
                     return Err(LexerError::DecimalMoreThanOnePeriod {
                         pos: lexer.current_pos(),
@@ -282,7 +482,24 @@ fn get_decimal(lexer: &mut Lexer) -> anyhow::Result<PosToken> {
                 builder.push(c1);
                 lexer.skip_i(1);
             }
-            ('_', '0'..='9') => {
+            ('e' | 'E', '0'..='9', _) | ('e' | 'E', '+' | '-', '0'..='9') => {
+                if exponent {
+                    return Err(LexerError::DecimalBadToken {
+                        c: c1,
+                        pos: lexer.current_pos(),
+                    }
+                    .into());
+                }
+
+                exponent = true;
+                builder.push(c1);
+                lexer.skip_i(1);
+                if !c2.is_ascii_digit() {
+                    builder.push(c2);
+                    lexer.skip_i(1);
+                }
+            }
+            ('_', '0'..='9', _) => {
                 lexer.skip_i(1);
             } // Skip
             _ => break,
@@ -293,11 +510,300 @@ fn get_decimal(lexer: &mut Lexer) -> anyhow::Result<PosToken> {
         unreachable!();
     }
 
-    Ok(PosToken::new(Token::NumberDecimal(builder), pos))
+    let suffix = get_number_suffix(lexer)?;
+    Ok(PosToken::new(
+        Token::NumberDecimal(builder, suffix),
+        pos,
+        lexer.span_from(start),
+    ))
 }
 
-fn get_macro(lexer: &mut Lexer) -> anyhow::Result<PosToken> {
+/// Lexes a 0x, 0o or 0b prefixed number, the token holds the digits only.
+fn get_radix(lexer: &mut Lexer, radix: u32) -> anyhow::Result<PosToken> {
+    let (pos, start) = (lexer.current_pos(), lexer.index);
+    let mut builder = String::new();
+
+    lexer.skip_i(2);
+
+    while lexer.within() {
+        let (c1, c2) = (
+            map_opt_char(lexer.peek().as_ref()),
+            map_opt_char(lexer.peek_i(1).as_ref()),
+        );
+        match (c1, c2) {
+            (c, _) if c.is_digit(radix) => {
+                builder.push(c);
+                lexer.skip_i(1);
+            }
+            ('_', c) if c.is_digit(radix) => {
+                lexer.skip_i(1);
+            } // Skip
+            ('u' | 'i', _) => break, // Suffix
+            (c, _) if c == '_' || c.is_alphanumeric() => {
+                return Err(LexerError::NumberBadDigit {
+                    c,
+                    radix,
+                    pos: lexer.current_pos(),
+                }
+                .into());
+            }
+            _ => break,
+        }
+    }
+
+    if builder.is_empty() {
+        return Err(LexerError::NumberMissingDigits { radix, pos }.into());
+    }
+    if u64::from_str_radix(&builder, radix).is_err() {
+        return Err(LexerError::NumberOutOfRange { radix, pos }.into());
+    }
+
+    let suffix = get_number_suffix(lexer)?;
+    let token = match radix {
+        16 => Token::NumberHex(builder, suffix),
+        8 => Token::NumberOctal(builder, suffix),
+        2 => Token::NumberBinary(builder, suffix),
+        _ => unreachable!(),
+    };
+    Ok(PosToken::new(token, pos, lexer.span_from(start)))
+}
+
+/// Lexes a type suffix directly after a number, like the u8 in 255u8.
+fn get_number_suffix(lexer: &mut Lexer) -> anyhow::Result<Option<RawTyping>> {
+    let pos = lexer.current_pos();
+    let mut builder = String::new();
+
+    while lexer.within() {
+        let c = map_opt_char(lexer.peek().as_ref());
+        if c.is_alphabetic() || (!builder.is_empty() && (c == '_' || c.is_alphanumeric())) {
+            builder.push(c);
+            lexer.skip_i(1);
+        }
+        else {
+            break;
+        }
+    }
+
+    if builder.is_empty() {
+        return Ok(None);
+    }
+
+    match RawTyping::from_suffix(&builder) {
+        Some(suffix) => Ok(Some(suffix)),
+        None => Err(LexerError::NumberBadSuffix {
+            suffix: builder,
+            pos,
+        }
+        .into()),
+    }
+}
+
+fn get_char(lexer: &mut Lexer) -> anyhow::Result<PosToken> {
+    let (pos, start) = (lexer.current_pos(), lexer.index);
+    lexer.skip_i(1);
+
+    let c = match lexer.peek() {
+        None | Some('\n') => return Err(LexerError::CharUnterminated { pos }.into()),
+        Some('\'') => return Err(LexerError::CharEmpty { pos }.into()),
+        Some('\\') => get_escape(lexer)?,
+        Some(c) => {
+            lexer.skip_i(1);
+            c
+        }
+    };
+
+    match lexer.peek() {
+        Some('\'') => {
+            lexer.skip_i(1);
+            Ok(PosToken::new(
+                Token::Character(c),
+                pos,
+                lexer.span_from(start),
+            ))
+        }
+        None | Some('\n') => Err(LexerError::CharUnterminated { pos }.into()),
+        Some(_) => {
+            // Find out if this was a multi char literal or a missing quote.
+            while let Some(c) = lexer.peek() {
+                match c {
+                    '\'' => return Err(LexerError::CharMultiple { pos }.into()),
+                    '\n' => break,
+                    _ => lexer.skip_i(1),
+                }
+            }
+            Err(LexerError::CharUnterminated { pos }.into())
+        }
+    }
+}
+
+/// A `'` followed by an identifier, where a label can be: right before a `:` or after break and continue.
+/// Anything else, like `'a'` or the unclosed `'a`, is a char.
+fn is_label(lexer: &mut Lexer, last: Option<&PosToken>) -> bool {
+    if !lexer
+        .peek_i(1)
+        .is_some_and(|c| c == '_' || c.is_alphabetic())
+    {
+        return false;
+    }
+    let mut i = 2;
+    while lexer
+        .peek_i(i)
+        .is_some_and(|c| c == '_' || c.is_alphanumeric())
+    {
+        i += 1;
+    }
+    match lexer.peek_i(i) {
+        Some('\'') => false,
+        Some(':') => true,
+        _ => last.is_some_and(|t| matches!(t.token, Token::Break | Token::Continue)),
+    }
+}
+
+fn get_label(lexer: &mut Lexer) -> PosToken {
+    let (pos, start) = (lexer.current_pos(), lexer.index);
+    lexer.skip_i(1);
+
+    let mut builder = String::new();
+    while let Some(c) = lexer.peek().filter(|c| *c == '_' || c.is_alphanumeric()) {
+        builder.push(c);
+        lexer.skip_i(1);
+    }
+    PosToken::new(Token::Label(builder), pos, lexer.span_from(start))
+}
+
+/// Lexes "", #""# and the raw r"" and r#""# strings.
+/// Raw strings do not have escapes or interpolation.
+fn get_string(lexer: &mut Lexer) -> anyhow::Result<PosToken> {
+    let (pos, start) = (lexer.current_pos(), lexer.index);
+
+    let raw = lexer.peek() == Some('r');
+    if raw {
+        lexer.skip_i(1);
+    }
+    let mut fence = 0;
+    while lexer.peek() == Some('#') {
+        fence += 1;
+        lexer.skip_i(1);
+    }
+    if lexer.peek() != Some('"') {
+        return Err(LexerError::StringMissingQuote {
+            pos: lexer.current_pos(),
+        }
+        .into());
+    }
+    lexer.skip_i(1);
+
+    let mut builder = String::new();
+    let mut fill = Vec::new();
+
+    loop {
+        match lexer.peek() {
+            None => return Err(LexerError::StringUnterminated { pos }.into()),
+            Some('"') if string_closes(lexer, fence) => {
+                lexer.skip_i(fence + 1);
+                break;
+            }
+            Some('\\') if !raw => builder.push(get_escape(lexer)?),
+            Some('{') if !raw => {
+                let id = get_interpolation(lexer)?;
+                fill.push((id, builder.len()));
+            }
+            Some(c) => {
+                builder.push(c);
+                lexer.skip_i(1);
+            }
+        }
+    }
+
+    fill.reverse();
+    Ok(PosToken::new(
+        Token::String(ChoppedString::new(builder, fill)),
+        pos,
+        lexer.span_from(start),
+    ))
+}
+
+fn string_closes(lexer: &mut Lexer, fence: usize) -> bool {
+    (1..=fence).all(|i| lexer.peek_i(i) == Some('#'))
+}
+
+/// Lexes a `{ident}` inside of a string.
+fn get_interpolation(lexer: &mut Lexer) -> anyhow::Result<Ident> {
+    let pos = lexer.current_pos();
+    lexer.skip_i(1);
+
+    let mut builder = String::new();
+    while let Some(c) = lexer.peek() {
+        if c == '_' || c.is_alphabetic() || (!builder.is_empty() && c.is_alphanumeric()) {
+            builder.push(c);
+            lexer.skip_i(1);
+        }
+        else {
+            break;
+        }
+    }
+
+    if builder.is_empty() || lexer.peek() != Some('}') {
+        return Err(LexerError::StringBadInterpolation { pos }.into());
+    }
+    lexer.skip_i(1);
+
+    Ok(builder)
+}
+
+/// Lexes an escape sequence starting at the `\`.
+fn get_escape(lexer: &mut Lexer) -> anyhow::Result<char> {
     let pos = lexer.current_pos();
+    lexer.skip_i(1);
+
+    let c = map_opt_char(lexer.peek().as_ref());
+    let escaped = match c {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        '0' => '\0',
+        '\\' | '\'' | '"' | '{' | '}' => c,
+        'u' => {
+            lexer.skip_i(1);
+            return get_unicode_escape(lexer, pos);
+        }
+        _ => return Err(LexerError::EscapeBad { c, pos }.into()),
+    };
+    lexer.skip_i(1);
+
+    Ok(escaped)
+}
+
+/// Lexes the `{1F600}` part of a `\u{1F600}` escape.
+fn get_unicode_escape(lexer: &mut Lexer, pos: Pos) -> anyhow::Result<char> {
+    if lexer.peek() != Some('{') {
+        return Err(LexerError::EscapeBadUnicode { pos }.into());
+    }
+    lexer.skip_i(1);
+
+    let mut builder = String::new();
+    loop {
+        match lexer.peek() {
+            Some('}') => {
+                lexer.skip_i(1);
+                break;
+            }
+            Some(c) if c.is_ascii_hexdigit() && builder.len() < 6 => {
+                builder.push(c);
+                lexer.skip_i(1);
+            }
+            _ => return Err(LexerError::EscapeBadUnicode { pos }.into()),
+        }
+    }
+
+    u32::from_str_radix(&builder, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| LexerError::EscapeBadUnicode { pos }.into())
+}
+
+fn get_macro(lexer: &mut Lexer) -> anyhow::Result<PosToken> {
+    let (pos, start) = (lexer.current_pos(), lexer.index);
     let mut builder = String::new();
 
     while lexer.within() {
@@ -318,11 +824,15 @@ fn get_macro(lexer: &mut Lexer) -> anyhow::Result<PosToken> {
         unreachable!();
     }
 
-    Ok(PosToken::new(Token::Ident(builder), pos))
+    Ok(PosToken::new(
+        Token::Ident(builder),
+        pos,
+        lexer.span_from(start),
+    ))
 }
 
 fn get_ident(lexer: &mut Lexer) -> anyhow::Result<PosToken> {
-    let pos = lexer.current_pos();
+    let (pos, start) = (lexer.current_pos(), lexer.index);
     let mut builder = String::new();
 
     while lexer.within() {
@@ -342,7 +852,7 @@ fn get_ident(lexer: &mut Lexer) -> anyhow::Result<PosToken> {
 
     macro_rules! tk {
         ($x:ident) => {{
-            Ok(PosToken::new(Token::$x, pos))
+            Ok(PosToken::new(Token::$x, pos, lexer.span_from(start)))
         }};
     }
 
@@ -358,8 +868,17 @@ fn get_ident(lexer: &mut Lexer) -> anyhow::Result<PosToken> {
         "guard" => tk!(Guard),
         "else" => tk!(Else),
         "for" => tk!(For),
+        "in" => tk!(In),
+        "by" => tk!(By),
         "while" => tk!(While),
         "fn" => tk!(Function),
-        _ => Ok(PosToken::new(Token::Ident(builder), pos)),
+        "ret" => tk!(Return),
+        "break" => tk!(Break),
+        "continue" => tk!(Continue),
+        _ => Ok(PosToken::new(
+            Token::Ident(builder),
+            pos,
+            lexer.span_from(start),
+        )),
     }
 }
@@ -4,20 +4,28 @@
 //
 
 pub mod data;
+pub mod diagnostics;
 mod lexer;
 
 mod interpreter;
 mod parser;
+mod source;
 #[cfg(test)]
 mod test;
 
-pub use interpreter::Interpreter;
+pub use interpreter::{
+    binary_ops::{saturating_op, wrapping_op},
+    Interpreter, RuntimeError,
+};
 pub use lexer::{lex, LexerError};
-pub use parser::parse;
+pub use parser::{parse, ParseError};
+pub use source::SourceFile;
 
 // TODO: Library should be wasm compliant.
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// Line and column start at 1, the column counts chars.
+/// Orders by line, then column.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Pos {
     line: usize,
     col: usize,
@@ -27,9 +35,78 @@ impl Pos {
     pub const fn new(line: usize, col: usize) -> Self {
         Self { line, col }
     }
+
+    #[must_use]
+    pub const fn line(self) -> usize {
+        self.line
+    }
+
+    #[must_use]
+    pub const fn col(self) -> usize {
+        self.col
+    }
+
+    /// Byte offset of this pos in source, None when it is outside of source.
+    /// The column after the last char of a line is the offset of the line end.
+    #[must_use]
+    pub fn to_offset(self, source: &str) -> Option<usize> {
+        let line_start = match self.line {
+            0 => return None,
+            1 => 0,
+            line => source.match_indices('\n').nth(line - 2)?.0 + 1,
+        };
+        let rest = &source[line_start..];
+        let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+        col_offset(line, self.col).map(|offset| line_start + offset)
+    }
 }
 impl std::fmt::Display for Pos {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({}:{})", self.line, self.col)
     }
 }
+
+/// Byte offset of a column in a line without its newline.
+fn col_offset(line: &str, col: usize) -> Option<usize> {
+    line.char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(line.len()))
+        .nth(col.checked_sub(1)?)
+}
+
+/// Byte offsets into the source, end is exclusive.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+impl Span {
+    #[must_use]
+    pub const fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Covers both spans and everything between them.
+    #[must_use]
+    pub fn to(self, other: Self) -> Self {
+        Self {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+
+    #[must_use]
+    pub const fn len(self) -> usize {
+        self.end - self.start
+    }
+
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.start == self.end
+    }
+}
+impl std::fmt::Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
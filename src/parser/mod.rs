@@ -2,25 +2,275 @@ mod parse_item;
 mod parse_tree;
 
 use crate::{
-    data::{Ast, Ident, PosAst, PosToken, RawTyping, Token},
-    Pos,
+    data::{Ast, DeclarationKind, Ident, PosAst, PosToken, RawTyping, Token},
+    Pos, Span,
 };
 use std::collections::{HashMap, HashSet};
 
 // TODO: Get rid of static?
-static EOF_TOKEN: PosToken = PosToken::eof(Pos::new(usize::MAX, usize::MAX));
+static EOF_TOKEN: PosToken = PosToken::eof(
+    Pos::new(usize::MAX, usize::MAX),
+    Span::new(usize::MAX, usize::MAX),
+);
+
+/// How deep expressions and blocks can be inside of each other, deeper scripts
+/// are an error instead of overflowing the stack while parsing or running them.
+pub const MAX_NESTING_DEPTH: usize = 256;
+
+/// The span is the source text the error underlines.
+#[derive(Debug, Eq, PartialEq)]
+pub enum ParseError {
+    ExpectedToken {
+        expected: Token,
+        found: Token,
+        pos: Pos,
+        span: Span,
+    },
+    ExpectedExpression {
+        found: Token,
+        pos: Pos,
+        span: Span,
+    },
+    ExpectedIdent {
+        found: Token,
+        pos: Pos,
+        span: Span,
+    },
+    UnknownType {
+        name: Ident,
+        pos: Pos,
+        span: Span,
+    },
+    ConstNotConstant {
+        id: Ident,
+        pos: Pos,
+        span: Span,
+    },
+    InvalidAssignTarget {
+        found: Token,
+        pos: Pos,
+        span: Span,
+    },
+    /// Pos and span are of the else block.
+    GuardNotDiverging {
+        pos: Pos,
+        span: Span,
+    },
+    /// found is break or continue.
+    OutsideLoop {
+        found: Token,
+        pos: Pos,
+        span: Span,
+    },
+    UndefinedLabel {
+        label: Ident,
+        pos: Pos,
+        span: Span,
+    },
+    DuplicateParameter {
+        id: Ident,
+        pos: Pos,
+        span: Span,
+    },
+    /// Pos and span are of the value after ret.
+    UnexpectedReturnValue {
+        function: Ident,
+        pos: Pos,
+        span: Span,
+    },
+    RequireParentheses {
+        first: Token,
+        first_pos: Pos,
+        first_span: Span,
+        found: Token,
+        pos: Pos,
+        span: Span,
+        suggestion: String,
+    },
+    NumberOutOfRange {
+        num: String,
+        ty: RawTyping,
+        pos: Pos,
+        span: Span,
+    },
+    NumberFloatSuffix {
+        num: String,
+        suffix: RawTyping,
+        pos: Pos,
+        span: Span,
+    },
+    /// Pos and span are of the first token past MAX_NESTING_DEPTH.
+    NestingDepth {
+        pos: Pos,
+        span: Span,
+    },
+}
+impl ParseError {
+    #[must_use]
+    pub const fn pos(&self) -> Pos {
+        match self {
+            Self::ExpectedToken { pos, .. }
+            | Self::ExpectedExpression { pos, .. }
+            | Self::ExpectedIdent { pos, .. }
+            | Self::UnknownType { pos, .. }
+            | Self::ConstNotConstant { pos, .. }
+            | Self::InvalidAssignTarget { pos, .. }
+            | Self::GuardNotDiverging { pos, .. }
+            | Self::OutsideLoop { pos, .. }
+            | Self::UndefinedLabel { pos, .. }
+            | Self::DuplicateParameter { pos, .. }
+            | Self::UnexpectedReturnValue { pos, .. }
+            | Self::RequireParentheses { pos, .. }
+            | Self::NumberOutOfRange { pos, .. }
+            | Self::NumberFloatSuffix { pos, .. }
+            | Self::NestingDepth { pos, .. } => *pos,
+        }
+    }
+
+    #[must_use]
+    pub const fn span(&self) -> Span {
+        match self {
+            Self::ExpectedToken { span, .. }
+            | Self::ExpectedExpression { span, .. }
+            | Self::ExpectedIdent { span, .. }
+            | Self::UnknownType { span, .. }
+            | Self::ConstNotConstant { span, .. }
+            | Self::InvalidAssignTarget { span, .. }
+            | Self::GuardNotDiverging { span, .. }
+            | Self::OutsideLoop { span, .. }
+            | Self::UndefinedLabel { span, .. }
+            | Self::DuplicateParameter { span, .. }
+            | Self::UnexpectedReturnValue { span, .. }
+            | Self::RequireParentheses { span, .. }
+            | Self::NumberOutOfRange { span, .. }
+            | Self::NumberFloatSuffix { span, .. }
+            | Self::NestingDepth { span, .. } => *span,
+        }
+    }
+}
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ExpectedToken {
+                expected,
+                found,
+                pos,
+                ..
+            } => {
+                write!(f, "Parser: Expected {expected} but found {found} at {pos}.")
+            }
+            Self::ExpectedExpression { found, pos, .. } => {
+                write!(
+                    f,
+                    "Parser: Expected an expression but found {found} at {pos}."
+                )
+            }
+            Self::ExpectedIdent { found, pos, .. } => {
+                write!(
+                    f,
+                    "Parser: Expected an identifier but found {found} at {pos}."
+                )
+            }
+            Self::UnknownType { name, pos, .. } => {
+                write!(f, "Parser: Unknown type '{name}' at {pos}.")
+            }
+            Self::ConstNotConstant { id, pos, .. } => {
+                write!(
+                    f,
+                    "Parser: const '{id}' must be known without running the script, but uses a variable at {pos}."
+                )
+            }
+            Self::InvalidAssignTarget { found, pos, .. } => {
+                write!(
+                    f,
+                    "Parser: Can not assign with {found} at {pos}, only variables can be assigned to."
+                )
+            }
+            Self::GuardNotDiverging { pos, .. } => {
+                write!(
+                    f,
+                    "Parser: The else block of guard at {pos} must end the scope with ret, break or continue."
+                )
+            }
+            Self::OutsideLoop { found, pos, .. } => {
+                write!(f, "Parser: {found} at {pos} is not inside of a loop.")
+            }
+            Self::UndefinedLabel { label, pos, .. } => {
+                write!(f, "Parser: No loop with the label '{label} around {pos}.")
+            }
+            Self::DuplicateParameter { id, pos, .. } => {
+                write!(f, "Parser: Parameter '{id}' at {pos} is already declared.")
+            }
+            Self::UnexpectedReturnValue { function, pos, .. } => {
+                write!(
+                    f,
+                    "Parser: Function '{function}' has no return type, but ret gives a value at {pos}."
+                )
+            }
+            Self::RequireParentheses {
+                first,
+                first_pos,
+                found,
+                pos,
+                suggestion,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Parser: {found} at {pos} can not be chained with {first} at {first_pos}, add parentheses like `{suggestion}`."
+                )
+            }
+            Self::NumberOutOfRange { num, ty, pos, .. } => {
+                write!(f, "Parser: Number {num} does not fit in {ty} at {pos}.")
+            }
+            Self::NumberFloatSuffix {
+                num, suffix, pos, ..
+            } => {
+                write!(
+                    f,
+                    "Parser: Float {num} can not have the integer suffix {suffix} at {pos}."
+                )
+            }
+            Self::NestingDepth { pos, .. } => {
+                write!(
+                    f,
+                    "Parser: Nested deeper than {MAX_NESTING_DEPTH} levels at {pos}."
+                )
+            }
+        }
+    }
+}
+impl std::error::Error for ParseError {}
 
 // TODO: Add environment to track idents.
 #[derive(Debug)]
 struct Parser {
     tokens: Vec<PosToken>,
     index: usize,
+    /// Labels of the loops around the current token, innermost last.
+    loops: Vec<Option<Ident>>,
+    /// Functions around the current token with their return type, innermost last.
+    functions: Vec<(Ident, Option<RawTyping>)>,
+    /// Levels around the current token, chained operators count as a level each.
+    depth: usize,
+    /// Most levels reached since the current operator chain started.
+    deepest: usize,
+    /// Names declared in the scopes around the current token, innermost last.
+    scopes: Vec<HashMap<Ident, DeclarationKind>>,
     // TODO: RawTyping should include possible types for type inference.
     // emu_env: Vec<HashMap<Ident, RawTyping>>,
 }
 impl Parser {
     fn new(tokens: Vec<PosToken>) -> Self {
-        Self { tokens, index: 0 }
+        Self {
+            tokens,
+            index: 0,
+            loops: Vec::new(),
+            functions: Vec::new(),
+            depth: 0,
+            deepest: 0,
+            scopes: vec![HashMap::new()],
+        }
     }
 
     fn within(&self) -> bool {
@@ -48,31 +298,96 @@ impl Parser {
     fn skip_i(&mut self, i: usize) {
         self.index += i;
     }
-}
 
-pub fn parse(tokens: Vec<PosToken>) -> anyhow::Result<PosAst> {
-    let mut parser = Parser::new(tokens);
+    /// Span of the last token that was skipped, used as the end of a node.
+    fn last_span(&self) -> Span {
+        self.index
+            .checked_sub(1)
+            .and_then(|i| self.tokens.get(i))
+            .map_or_else(Span::default, |t| t.span)
+    }
 
-    let root = PosAst::new(
-        Ast::Root(parse_tree::p_expression(&mut parser)?.into()),
-        Pos::new(0, 0),
-    );
+    /// Goes one level deeper than depth, or errors at the next token when that
+    /// is past MAX_NESTING_DEPTH.
+    fn deeper(&mut self, depth: usize) -> anyhow::Result<()> {
+        if depth >= MAX_NESTING_DEPTH {
+            let PosToken { pos, span, .. } = map_opt_token(self.peek());
+            return Err(ParseError::NestingDepth {
+                pos: *pos,
+                span: *span,
+            }
+            .into());
+        }
+        self.depth = depth + 1;
+        self.deepest = self.deepest.max(self.depth);
+        Ok(())
+    }
+
+    /// Runs f one level deeper.
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> anyhow::Result<T>) -> anyhow::Result<T> {
+        let depth = self.depth;
+        self.deeper(depth)?;
+        let result = f(self);
+        self.depth = depth;
+        result
+    }
+
+    /// Runs f in a new scope that starts with names.
+    fn scoped<T>(
+        &mut self,
+        names: HashMap<Ident, DeclarationKind>,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        self.scopes.push(names);
+        let result = f(self);
+        self.scopes.pop();
+        result
+    }
 
-    dbg!(&root);
+    fn declare(&mut self, id: Ident, kind: DeclarationKind) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(id, kind);
+        }
+    }
 
-    match parser.peek() {
-        Some(PosToken {
-            token: Token::Eof,
-            pos,
-        }) => println!("DBG: Found Eof at {pos:?}"),
-        _ => panic!("Required EOF token but not found!!!"),
+    /// Whether the closest declaration of id is a const.
+    fn is_const(&self, id: &Ident) -> bool {
+        self.scopes.iter().rev().find_map(|scope| scope.get(id)) == Some(&DeclarationKind::Const)
     }
 
-    Ok(root)
+    /// Skips the next token if it is the expected one.
+    fn expect(&mut self, expected: Token) -> anyhow::Result<Pos> {
+        let PosToken { token, pos, span } = map_opt_token(self.peek());
+        let pos = *pos;
+        if *token == expected {
+            self.skip();
+            Ok(pos)
+        }
+        else {
+            Err(ParseError::ExpectedToken {
+                expected,
+                found: token.clone(),
+                pos,
+                span: *span,
+            }
+            .into())
+        }
+    }
 }
 
-fn p_block() {
-    todo!()
+pub fn parse(mut tokens: Vec<PosToken>) -> anyhow::Result<PosAst> {
+    // TODO: Attach doc comments to declarations once those exist.
+    tokens.retain(|t| !t.token.is_doc_comment());
+    let mut parser = Parser::new(tokens);
+
+    let stmts = parse_tree::p_statements(&mut parser, &Token::Eof)?;
+    let end = map_opt_token(parser.peek()).span.end;
+
+    Ok(PosAst::new(
+        Ast::Root(stmts),
+        Pos::new(0, 0),
+        Span::new(0, end),
+    ))
 }
 
 fn map_opt_token(opt_token: Option<&PosToken>) -> &PosToken {
@@ -1,16 +1,82 @@
-use crate::data::{Ast, Token, Typing};
+use crate::{
+    data::{Ast, RawTyping, Token, Typing},
+    parser::ParseError,
+    Pos, Span,
+};
 
-// TODO: Support floats. uints?
-// TODO: Error with position.
-pub fn parse_number(num: &Token) -> anyhow::Result<Typing> {
-    match num {
-        Token::NumberDecimal(num) => {
-            assert!(!num.contains('.'), "No float support yet!");
-            Ok(Typing::Int64(i64::from_str_radix(num, 10)?))
-        }
-        Token::NumberHex(_) => todo!(),
-        Token::NumberOctal(_) => todo!(),
-        Token::NumberBinary(_) => todo!(),
+fn split_number(num: &Token) -> (&String, u32, &Option<RawTyping>, bool) {
+    let (num, radix, suffix) = match num {
+        Token::NumberDecimal(num, suffix) => (num, 10, suffix),
+        Token::NumberHex(num, suffix) => (num, 16, suffix),
+        Token::NumberOctal(num, suffix) => (num, 8, suffix),
+        Token::NumberBinary(num, suffix) => (num, 2, suffix),
         _ => unreachable!(),
+    };
+    let float = radix == 10 && num.contains(['.', 'e', 'E']);
+    (num, radix, suffix, float)
+}
+
+pub fn parse_number(num: &Token, pos: Pos, span: Span) -> anyhow::Result<Typing> {
+    let (num, radix, suffix, float) = split_number(num);
+
+    match suffix {
+        None if float => parse_float(num, pos, span),
+        None => parse_int(num, radix, pos, span),
+        Some(RawTyping::Float64) => parse_float(num, pos, span),
+        Some(suffix) if float => Err(ParseError::NumberFloatSuffix {
+            num: num.clone(),
+            suffix: *suffix,
+            pos,
+            span,
+        }
+        .into()),
+        Some(suffix) => {
+            let value = match suffix {
+                RawTyping::Int64 => i64::from_str_radix(num, radix).map(Typing::Int64),
+                RawTyping::UInt64 => u64::from_str_radix(num, radix).map(Typing::UInt64),
+                RawTyping::UInt8 => u8::from_str_radix(num, radix).map(Typing::UInt8),
+                _ => unreachable!(),
+            };
+            Ok(value.map_err(|_| ParseError::NumberOutOfRange {
+                num: num.clone(),
+                ty: *suffix,
+                pos,
+                span,
+            })?)
+        }
+    }
+}
+
+/// `-9223372036854775808` is `i64::MIN`, but its digits alone do not fit in
+/// Int64, so the parser folds the minus into the literal.
+pub fn is_min_int(num: &Token) -> bool {
+    let (num, radix, suffix, float) = split_number(num);
+    matches!(suffix, None | Some(RawTyping::Int64))
+        && !float
+        && u64::from_str_radix(num, radix) == Ok(i64::MIN.unsigned_abs())
+}
+
+/// Integers without a suffix are Int64 when they fit, otherwise UInt64.
+fn parse_int(num: &str, radix: u32, pos: Pos, span: Span) -> anyhow::Result<Typing> {
+    let num = u64::from_str_radix(num, radix).map_err(|_| ParseError::NumberOutOfRange {
+        num: num.to_string(),
+        ty: RawTyping::UInt64,
+        pos,
+        span,
+    })?;
+    Ok(i64::try_from(num).map_or(Typing::UInt64(num), Typing::Int64))
+}
+
+/// The lexer only produces valid floats, but too large ones are infinity.
+fn parse_float(num: &str, pos: Pos, span: Span) -> anyhow::Result<Typing> {
+    match num.parse::<f64>() {
+        Ok(float) if float.is_finite() => Ok(Typing::Float64(float)),
+        _ => Err(ParseError::NumberOutOfRange {
+            num: num.to_string(),
+            ty: RawTyping::Float64,
+            pos,
+            span,
+        }
+        .into()),
     }
 }
@@ -1,244 +1,917 @@
 use crate::{
-    data::{Ast, BinaryOperation, PosAst, PosToken, Token, UnaryOperation},
-    parser::{parse_item::parse_number, Parser},
+    data::{
+        Ast, BinaryOperation, DeclarationKind, Function, Ident, PosAst, PosToken, RawTyping, Token,
+        Typing, UnaryOperation,
+    },
+    parser::{
+        map_opt_token,
+        parse_item::{is_min_int, parse_number},
+        ParseError, Parser, MAX_NESTING_DEPTH,
+    },
+    Pos, Span,
 };
+use std::collections::HashMap;
+
+/// Statements until end, without taking end.
+/// Every statement needs a `;` after it, except the last one and blocks.
+/// The last one is the value when it has no `;`.
+pub(super) fn p_statements(parser: &mut Parser, end: &Token) -> anyhow::Result<Vec<PosAst>> {
+    let mut stmts = Vec::new();
+
+    loop {
+        while map_opt_token(parser.peek()).token == Token::SemiColon {
+            parser.skip();
+        }
+        let token = &map_opt_token(parser.peek()).token;
+        if token == end || *token == Token::Eof {
+            break;
+        }
+
+        let stmt = p_statement(parser)?;
+        let (stmt_pos, stmt_span) = (stmt.pos, stmt.span);
+        let PosToken { token, pos, span } = map_opt_token(parser.peek());
+        let stmt = match token {
+            Token::SemiColon => {
+                PosAst::new(Ast::Statement(stmt.into()), stmt_pos, stmt_span.to(*span))
+            }
+            token if token == end || *token == Token::Eof => stmt,
+            _ if is_block_like(&stmt) => {
+                PosAst::new(Ast::Statement(stmt.into()), stmt_pos, stmt_span)
+            }
+            _ => {
+                return Err(ParseError::ExpectedToken {
+                    expected: Token::SemiColon,
+                    found: token.clone(),
+                    pos: *pos,
+                    span: *span,
+                }
+                .into())
+            }
+        };
+        stmts.push(stmt);
+    }
+
+    Ok(stmts)
+}
+
+/// Expressions that end with a block do not need a `;` to be a statement.
+const fn is_block_like(ast: &PosAst) -> bool {
+    matches!(
+        ast.ast,
+        Ast::Block(_)
+            | Ast::If(..)
+            | Ast::Guard(..)
+            | Ast::While(..)
+            | Ast::For(..)
+            | Ast::Function(_)
+    )
+}
+
+pub(super) fn p_statement(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    match map_opt_token(parser.peek()).token {
+        Token::Const => p_declaration(parser, DeclarationKind::Const),
+        Token::Let => p_declaration(parser, DeclarationKind::Let),
+        Token::Var => p_declaration(parser, DeclarationKind::Var),
+        Token::Guard => p_guard(parser),
+        Token::Function => p_function(parser),
+        _ => p_expression(parser),
+    }
+}
 
-// TODO: FIX!!!
 pub(super) fn p_expression(parser: &mut Parser) -> anyhow::Result<PosAst> {
-    p_boolor(parser)
-}
-
-// Template
-// fn p_(parser: &mut Parser) -> anyhow::Result<PosAst> {
-//     let expr = (parser)?;
-//     match parser.peek() {
-//         Some(PosToken { token: Token::, pos}) => crate::bin_op!(, pos, expr, , parser),
-//         _ => Ok(expr),
-//     }
-// }
-
-macro_rules! bin_op {
-    ($t:ident, $p:ident, $e:ident, $f:ident, $par:ident) => {{
-        let pos = *$p;
-        $par.skip();
-        let other_expr = $f($par)?;
-        Ok(PosAst::new(
-            Ast::BinOp(BinaryOperation::$t, $e.into(), other_expr.into()),
+    parser.nested(|parser| match map_opt_token(parser.peek()).token {
+        Token::Return => p_return(parser),
+        Token::Break | Token::Continue => p_break(parser),
+        _ => p_assignment(parser),
+    })
+}
+
+/// `ret` or `ret expr`, there is no value when the statement ends right after `ret`.
+/// Only functions with a return type can give a value.
+fn p_return(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    let PosToken { pos, span, .. } = map_opt_token(parser.peek());
+    let (pos, span) = (*pos, *span);
+    parser.skip();
+
+    let val_pos = map_opt_token(parser.peek()).pos;
+    let val = p_optional_value(parser)?;
+    if let (Some(val), Some((function, None))) = (&val, parser.functions.last()) {
+        return Err(ParseError::UnexpectedReturnValue {
+            function: function.clone(),
+            pos: val_pos,
+            span: val.span,
+        }
+        .into());
+    }
+    let span = val.as_ref().map_or(span, |val| span.to(val.span));
+    Ok(PosAst::new(Ast::Return(val.map(Box::new)), pos, span))
+}
+
+/// Value after `ret` or `break`, none when the statement ends.
+fn p_optional_value(parser: &mut Parser) -> anyhow::Result<Option<PosAst>> {
+    match map_opt_token(parser.peek()).token {
+        Token::SemiColon | Token::RCurly | Token::Eof => Ok(None),
+        _ => Ok(Some(p_expression(parser)?)),
+    }
+}
+
+/// `break 'label value` or `continue 'label`, the label must be on a loop around it.
+fn p_break(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    let PosToken { token, pos, span } = map_opt_token(parser.peek());
+    let (token, pos, span) = (token.clone(), *pos, *span);
+    parser.skip();
+
+    if parser.loops.is_empty() {
+        return Err(ParseError::OutsideLoop {
+            found: token,
             pos,
-        ))
-    }};
+            span,
+        }
+        .into());
+    }
+    let label = match map_opt_token(parser.peek()) {
+        PosToken {
+            token: Token::Label(label),
+            pos,
+            span,
+        } => {
+            if !parser.loops.iter().any(|l| l.as_ref() == Some(label)) {
+                return Err(ParseError::UndefinedLabel {
+                    label: label.clone(),
+                    pos: *pos,
+                    span: *span,
+                }
+                .into());
+            }
+            let label = label.clone();
+            parser.skip();
+            Some(label)
+        }
+        _ => None,
+    };
+    let span = span.to(parser.last_span());
+
+    if token == Token::Continue {
+        return Ok(PosAst::new(Ast::Continue(label), pos, span));
+    }
+    let val = p_optional_value(parser)?;
+    let span = val.as_ref().map_or(span, |val| span.to(val.span));
+    Ok(PosAst::new(Ast::Break(label, val.map(Box::new)), pos, span))
+}
+
+/// `guard cond, let x = expr else { ... }`, the else block must diverge.
+fn p_guard(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    let PosToken { pos, span, .. } = map_opt_token(parser.peek());
+    let (pos, start) = (*pos, *span);
+    parser.skip();
+
+    let mut conds = Vec::new();
+    loop {
+        conds.push(match map_opt_token(parser.peek()).token {
+            Token::Const | Token::Let | Token::Var => p_statement(parser)?,
+            _ => p_expression(parser)?,
+        });
+        if map_opt_token(parser.peek()).token != Token::Comma {
+            break;
+        }
+        parser.skip();
+    }
+    parser.expect(Token::Else)?;
+
+    let otherwise = p_block(parser)?;
+    if !diverges(&otherwise) {
+        return Err(ParseError::GuardNotDiverging {
+            pos: otherwise.pos,
+            span: otherwise.span,
+        }
+        .into());
+    }
+
+    let span = start.to(parser.last_span());
+    Ok(PosAst::new(Ast::Guard(conds, otherwise.into()), pos, span))
+}
+
+/// Whether running the statement always leaves the enclosing block.
+fn diverges(ast: &PosAst) -> bool {
+    match &ast.ast {
+        Ast::Return(_) | Ast::Break(..) | Ast::Continue(_) => true,
+        Ast::Statement(a) => diverges(a),
+        Ast::Block(stmts) => stmts.iter().any(diverges),
+        Ast::If(_, then, Some(otherwise)) => diverges(then) && diverges(otherwise),
+        _ => false,
+    }
+}
+
+/// `let name: type = expr`, the type is optional.
+fn p_declaration(parser: &mut Parser, kind: DeclarationKind) -> anyhow::Result<PosAst> {
+    let start = map_opt_token(parser.peek()).span;
+    parser.skip();
+
+    let (id, pos, _) = p_ident(parser)?;
+    let ty = match map_opt_token(parser.peek()).token {
+        Token::Colon => {
+            parser.skip();
+            Some(p_type(parser)?)
+        }
+        _ => None,
+    };
+    parser.expect(Token::Assign)?;
+    let expr = p_expression(parser)?;
+
+    if kind == DeclarationKind::Const {
+        if let Some(var) = non_constant(parser, &expr) {
+            return Err(ParseError::ConstNotConstant {
+                id,
+                pos: var.pos,
+                span: var.span,
+            }
+            .into());
+        }
+    }
+    parser.declare(id.clone(), kind);
+
+    let span = start.to(expr.span);
+    Ok(PosAst::new(
+        Ast::Declaration(kind, id, ty, expr.into()),
+        pos,
+        span,
+    ))
+}
+
+/// Name of a type, like the int in `let x: int = 1`.
+fn p_type(parser: &mut Parser) -> anyhow::Result<RawTyping> {
+    let (name, pos, span) = p_ident(parser)?;
+    Ok(RawTyping::from_name(&name).ok_or(ParseError::UnknownType { name, pos, span })?)
+}
+
+/// `fn name(param: type, ...) -> type { ... }`, the return type is optional.
+fn p_function(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    let start = map_opt_token(parser.peek()).span;
+    parser.skip();
+
+    let (name, pos, _) = p_ident(parser)?;
+    parser.expect(Token::LParentheses)?;
+    let mut params: Vec<(Ident, RawTyping)> = Vec::new();
+    while map_opt_token(parser.peek()).token != Token::RParentheses {
+        let (id, pos, span) = p_ident(parser)?;
+        if params.iter().any(|(param, _)| *param == id) {
+            return Err(ParseError::DuplicateParameter { id, pos, span }.into());
+        }
+        parser.expect(Token::Colon)?;
+        params.push((id, p_type(parser)?));
+
+        if map_opt_token(parser.peek()).token != Token::Comma {
+            break;
+        }
+        parser.skip();
+    }
+    parser.expect(Token::RParentheses)?;
+
+    let ret = match map_opt_token(parser.peek()).token {
+        Token::Arrow => {
+            parser.skip();
+            Some(p_type(parser)?)
+        }
+        _ => None,
+    };
+
+    // Loops around the declaration can not be left from inside of the body, and
+    // the body only sees the global scope and its own, the same as a call.
+    let loops = std::mem::take(&mut parser.loops);
+    let scopes = parser.scopes.split_off(1);
+    let names = params
+        .iter()
+        .map(|(id, _)| (id.clone(), DeclarationKind::Let))
+        .collect();
+    parser.functions.push((name.clone(), ret));
+    let body = parser.scoped(names, p_block);
+    parser.functions.pop();
+    parser.loops = loops;
+    parser.scopes.extend(scopes);
+    let body = body?;
+
+    let span = start.to(body.span);
+    let function = Function {
+        name,
+        params,
+        ret,
+        body,
+    };
+    Ok(PosAst::new(Ast::Function(function.into()), pos, span))
 }
 
-macro_rules! unary_op {
-    ($t:ident, $p:ident, $f:ident, $par:ident) => {{
-        let pos = *$p;
-        $par.skip();
-        let other_expr = $f($par)?;
-        Ok(PosAst::new(
-            Ast::UnaryOp(UnaryOperation::$t, other_expr.into()),
+/// `name(args)`, a trailing comma is allowed.
+fn p_call(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    let (id, pos, start) = p_ident(parser)?;
+    parser.expect(Token::LParentheses)?;
+
+    let mut args = Vec::new();
+    while map_opt_token(parser.peek()).token != Token::RParentheses {
+        args.push(p_expression(parser)?);
+        if map_opt_token(parser.peek()).token != Token::Comma {
+            break;
+        }
+        parser.skip();
+    }
+    parser.expect(Token::RParentheses)?;
+
+    let span = start.to(parser.last_span());
+    Ok(PosAst::new(Ast::Call(id, args), pos, span))
+}
+
+fn p_ident(parser: &mut Parser) -> anyhow::Result<(Ident, Pos, Span)> {
+    match map_opt_token(parser.peek()) {
+        PosToken {
+            token: Token::Ident(id),
             pos,
-        ))
-    }};
+            span,
+        } => {
+            let ret = (id.clone(), *pos, *span);
+            parser.skip();
+            Ok(ret)
+        }
+        PosToken { token, pos, span } => Err(ParseError::ExpectedIdent {
+            found: token.clone(),
+            pos: *pos,
+            span: *span,
+        }
+        .into()),
+    }
+}
+
+/// Finds the first part of a const initializer that depends on a variable.
+/// Only literals, operators and consts declared before are constant.
+fn non_constant<'a>(parser: &Parser, ast: &'a PosAst) -> Option<&'a PosAst> {
+    let non_constant = |ast| non_constant(parser, ast);
+    match &ast.ast {
+        Ast::Value(Typing::String(string)) if !string.is_filled() => Some(ast),
+        Ast::Value(Typing::Ident(id)) if !parser.is_const(id) => Some(ast),
+        Ast::Value(_) => None,
+        Ast::UnaryOp(_, a) => non_constant(a),
+        Ast::BinOp(_, a1, a2) => non_constant(a1).or_else(|| non_constant(a2)),
+        Ast::Range(_, start, end, step) => non_constant(start)
+            .or_else(|| non_constant(end))
+            .or_else(|| step.as_deref().and_then(non_constant)),
+        _ => Some(ast),
+    }
 }
 
-// TODO: Let/Const/Var
 // TODO: Closures
-// TODO: Assignments
 // TODO: Ranges
 
-fn p_boolor(parser: &mut Parser) -> anyhow::Result<PosAst> {
-    let expr = p_boolxor(parser)?;
-    match parser.peek() {
-        Some(PosToken {
-            token: Token::BoolOr,
-            pos,
-        }) => bin_op!(BooleanOr, pos, expr, p_boolor, parser),
-        _ => Ok(expr),
-    }
+/// Rows of docs/EXPRESSION_PRECEDENCE.md, later variants bind tighter.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+enum Precedence {
+    Lowest,
+    BoolOr,
+    BoolXor,
+    BoolAnd,
+    Compare,
+    BitOr,
+    BitXor,
+    BitAnd,
+    BitShift,
+    Additive,
+    Multiplicative,
 }
+impl Precedence {
+    /// Operators that require parentheses when chained.
+    const fn is_non_associative(self) -> bool {
+        matches!(self, Self::Compare)
+    }
 
-fn p_boolxor(parser: &mut Parser) -> anyhow::Result<PosAst> {
-    let expr = p_booland(parser)?;
-    match parser.peek() {
-        Some(PosToken {
-            token: Token::BoolXor,
-            pos,
-        }) => bin_op!(BooleanXor, pos, expr, p_boolxor, parser),
-        _ => Ok(expr),
+    const fn of(op: &BinaryOperation) -> Self {
+        match op {
+            BinaryOperation::BooleanOr => Self::BoolOr,
+            BinaryOperation::BooleanXor => Self::BoolXor,
+            BinaryOperation::BooleanAnd => Self::BoolAnd,
+            BinaryOperation::Equal
+            | BinaryOperation::NotEqual
+            | BinaryOperation::Less
+            | BinaryOperation::LessEqual
+            | BinaryOperation::Greater
+            | BinaryOperation::GreaterEqual => Self::Compare,
+            BinaryOperation::BitwiseOr => Self::BitOr,
+            BinaryOperation::BitwiseXor => Self::BitXor,
+            BinaryOperation::BitwiseAnd => Self::BitAnd,
+            BinaryOperation::BitwiseShiftLeft | BinaryOperation::BitwiseShiftRight => {
+                Self::BitShift
+            }
+            BinaryOperation::Add | BinaryOperation::Subtract => Self::Additive,
+            BinaryOperation::Multiply | BinaryOperation::Divide | BinaryOperation::Modulus => {
+                Self::Multiplicative
+            }
+        }
     }
 }
 
-fn p_booland(parser: &mut Parser) -> anyhow::Result<PosAst> {
-    let expr = p_compare(parser)?;
-    match parser.peek() {
-        Some(PosToken {
-            token: Token::BoolAnd,
-            pos,
-        }) => bin_op!(BooleanAnd, pos, expr, p_booland, parser),
-        _ => Ok(expr),
+/// `=` has no operation, the compound assignments have the operation they apply.
+fn assign_op(token: &Token) -> Option<Option<BinaryOperation>> {
+    let op = match token {
+        Token::Assign => None,
+        Token::AssignMathMultiply => Some(BinaryOperation::Multiply),
+        Token::AssignMathDivide => Some(BinaryOperation::Divide),
+        Token::AssignMathModulus => Some(BinaryOperation::Modulus),
+        Token::AssignMathAdd => Some(BinaryOperation::Add),
+        Token::AssignMathSubtract => Some(BinaryOperation::Subtract),
+        Token::AssignBitLeft => Some(BinaryOperation::BitwiseShiftLeft),
+        Token::AssignBitRight => Some(BinaryOperation::BitwiseShiftRight),
+        Token::AssignBitAnd => Some(BinaryOperation::BitwiseAnd),
+        Token::AssignBitXor => Some(BinaryOperation::BitwiseXor),
+        Token::AssignBitOr => Some(BinaryOperation::BitwiseOr),
+        _ => return None,
+    };
+    Some(op)
+}
+
+/// Assignments are right to left and bind the loosest, so the right side is a full expression.
+fn p_assignment(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    if matches!(map_opt_token(parser.peek()).token, Token::Ident(_))
+        && assign_op(&map_opt_token(parser.peek_i(1)).token).is_some()
+    {
+        return p_assign(parser);
+    }
+
+    let expr = p_range(parser)?;
+    let PosToken { token, pos, span } = map_opt_token(parser.peek());
+    if assign_op(token).is_some() {
+        return Err(ParseError::InvalidAssignTarget {
+            found: token.clone(),
+            pos: *pos,
+            span: *span,
+        }
+        .into());
     }
+    Ok(expr)
 }
 
-fn p_compare(parser: &mut Parser) -> anyhow::Result<PosAst> {
-    let expr = p_bitor(parser)?;
-    match parser.peek() {
-        Some(PosToken {
-            token: Token::CompareEqual,
-            pos,
-        }) => bin_op!(Equal, pos, expr, p_compare, parser),
-        Some(PosToken {
-            token: Token::CompareNotEqual,
-            pos,
-        }) => bin_op!(NotEqual, pos, expr, p_compare, parser),
-        Some(PosToken {
-            token: Token::CompareLess,
-            pos,
-        }) => bin_op!(Less, pos, expr, p_compare, parser),
-        Some(PosToken {
-            token: Token::CompareGreater,
-            pos,
-        }) => bin_op!(Greater, pos, expr, p_compare, parser),
-        Some(PosToken {
-            token: Token::CompareLessEqual,
-            pos,
-        }) => bin_op!(LessEqual, pos, expr, p_compare, parser),
-        Some(PosToken {
-            token: Token::CompareGreaterEqual,
+/// `x = expr`, `x op= expr` becomes `x = x op expr`.
+fn p_assign(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    let (first, second) = (
+        map_opt_token(parser.peek()),
+        map_opt_token(parser.peek_i(1)),
+    );
+    let (
+        PosToken {
+            token: Token::Ident(id),
             pos,
-        }) => bin_op!(GreaterEqual, pos, expr, p_compare, parser),
-        _ => Ok(expr),
+            span,
+        },
+        Some(op),
+    ) = (first, assign_op(&second.token))
+    else {
+        unreachable!("p_assignment checks for an assignment.")
+    };
+
+    let (id, pos, target_span, op_pos) = (id.clone(), *pos, *span, second.pos);
+    parser.skip_i(2);
+    let mut expr = parser.nested(p_assignment)?;
+    let span = target_span.to(expr.span);
+
+    if let Some(op) = op {
+        let target = PosAst::new(Ast::Value(Typing::Ident(id.clone())), pos, target_span);
+        expr = PosAst::new(Ast::BinOp(op, target.into(), expr.into()), op_pos, span);
     }
+    Ok(PosAst::new(Ast::Assignment(id, expr.into()), pos, span))
 }
 
-fn p_bitor(parser: &mut Parser) -> anyhow::Result<PosAst> {
-    let expr = p_bitxor(parser)?;
-    match parser.peek() {
-        Some(PosToken {
-            token: Token::BitOr,
-            pos,
-        }) => bin_op!(BitwiseOr, pos, expr, p_bitor, parser),
-        _ => Ok(expr),
+/// `start..<end` or `start..=end`, with an optional `by step`.
+/// Ranges bind looser than every binary operator and can not be chained.
+fn p_range(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    let start = p_binary(parser, Precedence::Lowest)?;
+    match map_opt_token(parser.peek()).token {
+        Token::RangeExclusive | Token::RangeInclusive => p_range_end(parser, start),
+        _ => Ok(start),
     }
 }
 
-fn p_bitxor(parser: &mut Parser) -> anyhow::Result<PosAst> {
-    let expr = p_bitand(parser)?;
-    match parser.peek() {
-        Some(PosToken {
-            token: Token::BitXor,
-            pos,
-        }) => bin_op!(BitwiseXor, pos, expr, p_bitxor, parser),
-        _ => Ok(expr),
+/// Everything of a range after its start.
+fn p_range_end(parser: &mut Parser, start: PosAst) -> anyhow::Result<PosAst> {
+    let PosToken { token, pos, span } = map_opt_token(parser.peek());
+    let inclusive = match token {
+        Token::RangeExclusive => false,
+        Token::RangeInclusive => true,
+        _ => return Ok(start),
+    };
+    let (first, pos, first_span) = (token.clone(), *pos, *span);
+    parser.skip();
+
+    let end = p_binary(parser, Precedence::Lowest)?;
+    let step = match map_opt_token(parser.peek()).token {
+        Token::By => {
+            parser.skip();
+            Some(Box::new(p_binary(parser, Precedence::Lowest)?))
+        }
+        _ => None,
+    };
+    let span = start.span.to(parser.last_span());
+    let range = PosAst::new(
+        Ast::Range(inclusive, start.into(), end.into(), step),
+        pos,
+        span,
+    );
+
+    let PosToken { token, pos, span } = map_opt_token(parser.peek());
+    if matches!(token, Token::RangeExclusive | Token::RangeInclusive) {
+        let (found, found_pos, found_span) = (token.clone(), *pos, *span);
+        parser.skip();
+        let other = p_binary(parser, Precedence::Lowest)
+            .map_or_else(|_| "...".to_string(), |e| to_source(&e));
+        return Err(ParseError::RequireParentheses {
+            first,
+            first_pos: range.pos,
+            first_span,
+            suggestion: format!("({}) {} {other}", to_source(&range), range_op(&found)),
+            found,
+            pos: found_pos,
+            span: found_span,
+        }
+        .into());
     }
+    Ok(range)
 }
 
-fn p_bitand(parser: &mut Parser) -> anyhow::Result<PosAst> {
-    let expr = p_bitshift(parser)?;
-    match parser.peek() {
-        Some(PosToken {
-            token: Token::BitAnd,
-            pos,
-        }) => bin_op!(BitwiseAnd, pos, expr, p_bitand, parser),
-        _ => Ok(expr),
+const fn range_op(token: &Token) -> &'static str {
+    match token {
+        Token::RangeInclusive => "..=",
+        _ => "..<",
     }
 }
 
-fn p_bitshift(parser: &mut Parser) -> anyhow::Result<PosAst> {
-    let expr = p_additive(parser)?;
-    match parser.peek() {
-        Some(PosToken {
-            token: Token::BitLeft,
-            pos,
-        }) => bin_op!(BitwiseShiftLeft, pos, expr, p_bitshift, parser),
-        Some(PosToken {
-            token: Token::BitRight,
-            pos,
-        }) => bin_op!(BitwiseShiftRight, pos, expr, p_bitshift, parser),
-        _ => Ok(expr),
+fn infix_op(token: &Token) -> Option<(Precedence, BinaryOperation)> {
+    let op = match token {
+        Token::BoolOr => (Precedence::BoolOr, BinaryOperation::BooleanOr),
+        Token::BoolXor => (Precedence::BoolXor, BinaryOperation::BooleanXor),
+        Token::BoolAnd => (Precedence::BoolAnd, BinaryOperation::BooleanAnd),
+        Token::CompareEqual => (Precedence::Compare, BinaryOperation::Equal),
+        Token::CompareNotEqual => (Precedence::Compare, BinaryOperation::NotEqual),
+        Token::CompareLess => (Precedence::Compare, BinaryOperation::Less),
+        Token::CompareLessEqual => (Precedence::Compare, BinaryOperation::LessEqual),
+        Token::CompareGreater => (Precedence::Compare, BinaryOperation::Greater),
+        Token::CompareGreaterEqual => (Precedence::Compare, BinaryOperation::GreaterEqual),
+        Token::BitOr => (Precedence::BitOr, BinaryOperation::BitwiseOr),
+        Token::BitXor => (Precedence::BitXor, BinaryOperation::BitwiseXor),
+        Token::BitAnd => (Precedence::BitAnd, BinaryOperation::BitwiseAnd),
+        Token::BitLeft => (Precedence::BitShift, BinaryOperation::BitwiseShiftLeft),
+        Token::BitRight => (Precedence::BitShift, BinaryOperation::BitwiseShiftRight),
+        Token::MathAdd => (Precedence::Additive, BinaryOperation::Add),
+        Token::MathSubtract => (Precedence::Additive, BinaryOperation::Subtract),
+        Token::MathMultiply => (Precedence::Multiplicative, BinaryOperation::Multiply),
+        Token::MathDivide => (Precedence::Multiplicative, BinaryOperation::Divide),
+        Token::MathModulus => (Precedence::Multiplicative, BinaryOperation::Modulus),
+        _ => return None,
+    };
+    Some(op)
+}
+
+/// Precedence climbing, parses operators that bind tighter than min.
+/// All binary operators are left to right, so the right side only takes tighter operators.
+/// A chain does not recurse, but each operator is a level above the deeper of its sides.
+fn p_binary(parser: &mut Parser, min: Precedence) -> anyhow::Result<PosAst> {
+    let (base, deepest) = (parser.depth, parser.deepest);
+    parser.deepest = base;
+    let mut expr = p_unary(parser)?;
+    let mut height = parser.deepest - base;
+    let mut last: Option<(Precedence, PosToken)> = None;
+
+    loop {
+        let PosToken { token, pos, span } = map_opt_token(parser.peek());
+        let Some((prec, op)) = infix_op(token)
+        else {
+            break;
+        };
+        if prec <= min {
+            break;
+        }
+
+        let found = PosToken::new(token.clone(), *pos, *span);
+        parser.skip();
+        parser.deepest = base;
+        let other_expr = p_binary(parser, prec);
+
+        if let Some((last_prec, first)) = last.take() {
+            if last_prec == prec && prec.is_non_associative() {
+                return Err(require_parentheses(first, &expr, op, other_expr, found));
+            }
+        }
+
+        let other_expr = other_expr?;
+        height = height.max(parser.deepest - base) + 1;
+        if base + height > MAX_NESTING_DEPTH {
+            return Err(ParseError::NestingDepth {
+                pos: found.pos,
+                span: found.span,
+            }
+            .into());
+        }
+        let span = expr.span.to(other_expr.span);
+        expr = PosAst::new(
+            Ast::BinOp(op, expr.into(), other_expr.into()),
+            found.pos,
+            span,
+        );
+        last = Some((prec, found));
     }
+
+    parser.deepest = deepest.max(base + height);
+    Ok(expr)
 }
 
-fn p_additive(parser: &mut Parser) -> anyhow::Result<PosAst> {
-    let expr = p_multiplicative(parser)?;
-    match parser.peek() {
-        Some(PosToken {
-            token: Token::MathAdd,
-            pos,
-        }) => bin_op!(Add, pos, expr, p_additive, parser),
-        Some(PosToken {
-            token: Token::MathSubtract,
-            pos,
-        }) => bin_op!(Subtract, pos, expr, p_additive, parser),
-        _ => Ok(expr),
+/// Suggests grouping from the left, the same as other operators.
+fn require_parentheses(
+    first: PosToken,
+    expr: &PosAst,
+    op: BinaryOperation,
+    other_expr: anyhow::Result<PosAst>,
+    found: PosToken,
+) -> anyhow::Error {
+    let other = other_expr.map_or_else(|_| "...".to_string(), |e| to_source(&e));
+    ParseError::RequireParentheses {
+        first: first.token,
+        first_pos: first.pos,
+        first_span: first.span,
+        suggestion: format!("({}) {op} {other}", to_source(expr)),
+        found: found.token,
+        pos: found.pos,
+        span: found.span,
     }
+    .into()
 }
 
-fn p_multiplicative(parser: &mut Parser) -> anyhow::Result<PosAst> {
-    let expr = p_unary(parser)?;
-    match parser.peek() {
-        Some(PosToken {
-            token: Token::MathMultiply,
-            pos,
-        }) => bin_op!(Multiply, pos, expr, p_multiplicative, parser),
-        Some(PosToken {
-            token: Token::MathDivide,
-            pos,
-        }) => bin_op!(Divide, pos, expr, p_multiplicative, parser),
-        Some(PosToken {
-            token: Token::MathModulus,
-            pos,
-        }) => bin_op!(Modulus, pos, expr, p_multiplicative, parser),
-        _ => Ok(expr),
+/// Writes an expression back as source, only adding the parentheses it needs.
+fn to_source(ast: &PosAst) -> String {
+    match &ast.ast {
+        Ast::UnaryOp(op, a) => match &a.ast {
+            Ast::BinOp(..) => format!("{op}({})", to_source(a)),
+            _ => format!("{op}{}", to_source(a)),
+        },
+        Ast::BinOp(op, a1, a2) => {
+            let prec = Precedence::of(op);
+            let side = |a: &PosAst, right: bool| match &a.ast {
+                Ast::BinOp(child, ..)
+                    if Precedence::of(child) < prec
+                        || (Precedence::of(child) == prec
+                            && (right || prec.is_non_associative())) =>
+                {
+                    format!("({})", to_source(a))
+                }
+                _ => to_source(a),
+            };
+            format!("{} {op} {}", side(a1, false), side(a2, true))
+        }
+        Ast::Range(inclusive, start, end, step) => {
+            let op = if *inclusive { "..=" } else { "..<" };
+            let range = format!("{} {op} {}", to_source(start), to_source(end));
+            match step {
+                Some(step) => format!("{range} by {}", to_source(step)),
+                None => range,
+            }
+        }
+        Ast::Value(val) => match val {
+            Typing::Float64(v) => format!("{v:?}"),
+            Typing::UInt64(v) => format!("{v}u64"),
+            Typing::UInt8(v) => format!("{v}u8"),
+            Typing::Character(c) => format!("{c:?}"),
+            Typing::String(s) => format!("{:?}", s.string()),
+            val => val.to_string(),
+        },
+        _ => "...".to_string(),
     }
 }
 
 fn p_unary(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    let op = match map_opt_token(parser.peek()).token {
+        Token::BitNot => UnaryOperation::BitwiseNot,
+        Token::BoolNot => UnaryOperation::BooleanNot,
+        Token::MathSubtract
+            if parser
+                .peek_i(1)
+                .is_some_and(|num| num.token.is_number() && is_min_int(&num.token)) =>
+        {
+            return p_min_int(parser);
+        }
+        Token::MathSubtract => UnaryOperation::Negate,
+        _ => return p_primary(parser),
+    };
+    p_unary_op(parser, op)
+}
+
+fn p_unary_op(parser: &mut Parser, op: UnaryOperation) -> anyhow::Result<PosAst> {
+    let PosToken { pos, span, .. } = map_opt_token(parser.peek());
+    let (pos, span) = (*pos, *span);
+    parser.skip();
+    let other_expr = parser.nested(p_unary)?;
+    let span = span.to(other_expr.span);
+    Ok(PosAst::new(Ast::UnaryOp(op, other_expr.into()), pos, span))
+}
+
+/// `-9223372036854775808` as one literal.
+fn p_min_int(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    let PosToken { pos, span, .. } = map_opt_token(parser.peek());
+    let ret = PosAst::new(
+        Ast::Value(Typing::Int64(i64::MIN)),
+        *pos,
+        span.to(map_opt_token(parser.peek_i(1)).span),
+    );
+    parser.skip_i(2);
+    Ok(ret)
+}
+
+/// Only picks the function for the next token, so nesting through it uses little stack.
+fn p_primary(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    match map_opt_token(parser.peek()).token {
+        Token::Ident(_) if map_opt_token(parser.peek_i(1)).token == Token::LParentheses => {
+            p_call(parser)
+        }
+        Token::LCurly => p_block(parser),
+        Token::If => p_if(parser),
+        Token::While | Token::For | Token::Label(_) => p_loop(parser),
+        Token::LParentheses => p_parentheses(parser),
+        _ => p_literal(parser),
+    }
+}
+
+/// `(expr)`, the node covers the parentheses too.
+fn p_parentheses(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    let start = map_opt_token(parser.peek()).span;
+    parser.skip();
+    let mut expr = p_expression(parser)?;
+    parser.expect(Token::RParentheses)?;
+    expr.span = start.to(parser.last_span());
+    Ok(expr)
+}
+
+/// A variable or a value written out.
+fn p_literal(parser: &mut Parser) -> anyhow::Result<PosAst> {
     match parser.peek() {
         Some(PosToken {
-            token: Token::BitNot,
+            token: Token::Ident(id),
             pos,
-        }) => unary_op!(BooleanNot, pos, p_unary, parser),
+            span,
+        }) => {
+            let ret = PosAst::new(Ast::Value(Typing::Ident(id.clone())), *pos, *span);
+            parser.skip();
+            Ok(ret)
+        }
         Some(PosToken {
-            token: Token::BoolNot,
+            token: token @ (Token::True | Token::False),
             pos,
-        }) => unary_op!(BitwiseNot, pos, p_unary, parser),
+            span,
+        }) => {
+            let ret = PosAst::new(
+                Ast::Value(Typing::Boolean(*token == Token::True)),
+                *pos,
+                *span,
+            );
+            parser.skip();
+            Ok(ret)
+        }
         Some(PosToken {
-            token: Token::MathSubtract,
+            token: Token::Character(c),
             pos,
-        }) => unary_op!(Negate, pos, p_unary, parser),
-        _ => p_primary(parser),
-    }
-}
-
-fn p_primary(parser: &mut Parser) -> anyhow::Result<PosAst> {
-    match parser.peek() {
-        Some(PosToken {
-            token: Token::Ident(id),
-            ..
-        }) => todo!(),
+            span,
+        }) => {
+            let ret = PosAst::new(Ast::Value(Typing::Character(*c)), *pos, *span);
+            parser.skip();
+            Ok(ret)
+        }
         Some(PosToken {
-            token: Token::LParentheses,
-            ..
+            token: Token::String(string),
+            pos,
+            span,
         }) => {
+            let ret = PosAst::new(
+                Ast::Value(Typing::String(string.clone().into())),
+                *pos,
+                *span,
+            );
             parser.skip();
-            let expr = p_expression(parser)?;
-            match parser.peek() {
-                Some(PosToken {
-                    token: Token::RParentheses,
-                    ..
-                }) => {
-                    parser.skip();
-                    Ok(expr)
-                }
-                item => panic!("Invalid token: {item:?}"),
-            }
+            Ok(ret)
         }
-        Some(PosToken { token, pos }) if token.is_number() => {
-            let wrapped = parse_number(token)?;
-            let ret = PosAst::new(Ast::Value(wrapped), *pos);
+        Some(PosToken { token, pos, span }) if token.is_number() => {
+            let wrapped = parse_number(token, *pos, *span)?;
+            let ret = PosAst::new(Ast::Value(wrapped), *pos, *span);
             parser.skip();
             Ok(ret)
         }
-        item => panic!("Bad data!!! {item:?}"),
+        item => {
+            let PosToken { token, pos, span } = map_opt_token(item);
+            Err(ParseError::ExpectedExpression {
+                found: token.clone(),
+                pos: *pos,
+                span: *span,
+            }
+            .into())
+        }
     }
 }
+
+/// `{ statements }`
+fn p_block(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    let PosToken { pos, span, .. } = map_opt_token(parser.peek());
+    let (pos, start) = (*pos, *span);
+    parser.expect(Token::LCurly)?;
+
+    let stmts = parser.nested(|parser| {
+        parser.scoped(HashMap::new(), |parser| {
+            p_statements(parser, &Token::RCurly)
+        })
+    })?;
+    parser.expect(Token::RCurly)?;
+
+    let span = start.to(parser.last_span());
+    Ok(PosAst::new(Ast::Block(stmts), pos, span))
+}
+
+/// `if cond { ... }` with optional `else { ... }` or `else if ...`.
+fn p_if(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    let PosToken { pos, span, .. } = map_opt_token(parser.peek());
+    let (pos, start) = (*pos, *span);
+    parser.expect(Token::If)?;
+
+    let cond = p_expression(parser)?;
+    let then = p_block(parser)?;
+    let otherwise = if map_opt_token(parser.peek()).token == Token::Else {
+        parser.skip();
+        match map_opt_token(parser.peek()).token {
+            Token::If => Some(parser.nested(p_if)?.into()),
+            _ => Some(p_block(parser)?.into()),
+        }
+    }
+    else {
+        None
+    };
+
+    let span = start.to(parser.last_span());
+    Ok(PosAst::new(
+        Ast::If(cond.into(), then.into(), otherwise),
+        pos,
+        span,
+    ))
+}
+
+/// A loop with an optional `'label:` in front.
+fn p_loop(parser: &mut Parser) -> anyhow::Result<PosAst> {
+    let PosToken { token, pos, span } = map_opt_token(parser.peek());
+    let (pos, start) = (*pos, *span);
+    let label = match token {
+        Token::Label(label) => {
+            let label = label.clone();
+            parser.skip();
+            parser.expect(Token::Colon)?;
+            Some(label)
+        }
+        _ => None,
+    };
+
+    let PosToken {
+        token,
+        pos: loop_pos,
+        span: loop_span,
+    } = map_opt_token(parser.peek());
+    let (token, loop_pos, loop_span) = (token.clone(), *loop_pos, *loop_span);
+    let head = match token {
+        Token::While => {
+            parser.skip();
+            LoopHead::While(p_expression(parser)?)
+        }
+        Token::For => {
+            parser.skip();
+            let (id, ..) = p_ident(parser)?;
+            parser.expect(Token::In)?;
+            LoopHead::For(id, p_expression(parser)?)
+        }
+        found => {
+            return Err(ParseError::ExpectedToken {
+                expected: Token::While,
+                found,
+                pos: loop_pos,
+                span: loop_span,
+            }
+            .into())
+        }
+    };
+
+    let names = match &head {
+        LoopHead::For(id, _) => HashMap::from([(id.clone(), DeclarationKind::Let)]),
+        LoopHead::While(_) => HashMap::new(),
+    };
+    parser.loops.push(label.clone());
+    let body = parser.scoped(names, p_block);
+    parser.loops.pop();
+    let body = Box::new(body?);
+
+    let span = start.to(parser.last_span());
+    let ast = match head {
+        LoopHead::While(cond) => Ast::While(label, cond.into(), body),
+        LoopHead::For(id, iter) => Ast::For(label, id, iter.into(), body),
+    };
+    Ok(PosAst::new(ast, pos, span))
+}
+
+/// Everything of a loop before its body.
+enum LoopHead {
+    While(PosAst),
+    /// `for id in iter`
+    For(Ident, PosAst),
+}
@@ -0,0 +1,73 @@
+use crate::{col_offset, Pos, Span};
+
+/// A script and where its lines start, for turning a Pos or Span back into source text.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceFile {
+    name: String,
+    text: String,
+    /// Byte offset of the start of every line.
+    line_starts: Vec<usize>,
+}
+impl SourceFile {
+    #[must_use]
+    pub fn new(name: impl Into<String>, text: impl Into<String>) -> Self {
+        let text = text.into();
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            name: name.into(),
+            text,
+            line_starts,
+        }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Text of a line without its newline, lines start at 1.
+    #[must_use]
+    pub fn line(&self, line: usize) -> Option<&str> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self
+            .line_starts
+            .get(line)
+            .map_or(self.text.len(), |next| next - 1);
+        Some(&self.text[start..end])
+    }
+
+    /// Same as Pos::to_offset, without searching for the line.
+    #[must_use]
+    pub fn offset(&self, pos: Pos) -> Option<usize> {
+        let line = self.line(pos.line())?;
+        col_offset(line, pos.col()).map(|offset| self.line_starts[pos.line() - 1] + offset)
+    }
+
+    /// Pos of a byte offset, None when it is outside of the text or inside of a char.
+    #[must_use]
+    pub fn pos(&self, offset: usize) -> Option<Pos> {
+        if !self.text.is_char_boundary(offset) {
+            return None;
+        }
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let start = self.line_starts[line - 1];
+        Some(Pos::new(line, self.text[start..offset].chars().count() + 1))
+    }
+
+    #[must_use]
+    pub fn slice(&self, span: Span) -> Option<&str> {
+        self.text.get(span.start..span.end)
+    }
+}
@@ -0,0 +1,428 @@
+use crate::{
+    data::{BinaryOperation, RawTyping, Typing},
+    saturating_op,
+    test::{run, run_err},
+    wrapping_op, Pos, RuntimeError, Span,
+};
+
+fn val(contents: &str) -> Typing {
+    run(contents).unwrap()
+}
+
+fn mismatch(contents: &str) -> (BinaryOperation, RawTyping, RawTyping) {
+    match run_err(contents) {
+        RuntimeError::BinaryTypeMismatch { op, lhs, rhs, .. } => (op, lhs, rhs),
+        err => panic!("Expected BinaryTypeMismatch, got {err:?}"),
+    }
+}
+
+#[test]
+fn test_int64() {
+    assert_eq!(val("7 * 3"), Typing::Int64(21));
+    assert_eq!(val("7 / 2"), Typing::Int64(3));
+    assert_eq!(val("-7 / 2"), Typing::Int64(-3));
+    assert_eq!(val("-7 % 3"), Typing::Int64(-1));
+    assert_eq!(val("7 + 3"), Typing::Int64(10));
+    assert_eq!(val("7 - 10"), Typing::Int64(-3));
+    assert_eq!(val("0b1100 & 0b1010"), Typing::Int64(0b1000));
+    assert_eq!(val("0b1100 ^ 0b1010"), Typing::Int64(0b0110));
+    assert_eq!(val("0b1100 | 0b1010"), Typing::Int64(0b1110));
+    assert_eq!(val("1 << 10"), Typing::Int64(1024));
+    assert_eq!(val("-16 >> 2"), Typing::Int64(-4));
+    assert_eq!(val("1 == 1"), Typing::Boolean(true));
+    assert_eq!(val("1 != 1"), Typing::Boolean(false));
+    assert_eq!(val("-1 < 1"), Typing::Boolean(true));
+    assert_eq!(val("1 <= 1"), Typing::Boolean(true));
+    assert_eq!(val("1 > 1"), Typing::Boolean(false));
+    assert_eq!(val("2 >= 1"), Typing::Boolean(true));
+}
+
+#[test]
+fn test_uint64() {
+    assert_eq!(val("7u64 * 3u64"), Typing::UInt64(21));
+    assert_eq!(val("7u64 / 2u64"), Typing::UInt64(3));
+    assert_eq!(val("7u64 % 4u64"), Typing::UInt64(3));
+    assert_eq!(val("18446744073709551614 + 1u64"), Typing::UInt64(u64::MAX));
+    assert_eq!(val("7u64 - 3u64"), Typing::UInt64(4));
+    assert_eq!(val("6u64 & 3u64"), Typing::UInt64(2));
+    assert_eq!(val("6u64 ^ 3u64"), Typing::UInt64(5));
+    assert_eq!(val("6u64 | 3u64"), Typing::UInt64(7));
+    assert_eq!(val("1u64 << 63"), Typing::UInt64(1 << 63));
+    assert_eq!(val("18446744073709551615 >> 60u8"), Typing::UInt64(15));
+    assert_eq!(val("1u64 < 2u64"), Typing::Boolean(true));
+    assert_eq!(val("2u64 == 2u64"), Typing::Boolean(true));
+}
+
+#[test]
+fn test_uint8() {
+    assert_eq!(val("15u8 * 17u8"), Typing::UInt8(255));
+    assert_eq!(val("255u8 / 2u8"), Typing::UInt8(127));
+    assert_eq!(val("255u8 % 16u8"), Typing::UInt8(15));
+    assert_eq!(val("200u8 + 55u8"), Typing::UInt8(255));
+    assert_eq!(val("200u8 - 55u8"), Typing::UInt8(145));
+    assert_eq!(val("0xF0u8 & 0x3Cu8"), Typing::UInt8(0x30));
+    assert_eq!(val("0xF0u8 ^ 0x3Cu8"), Typing::UInt8(0xCC));
+    assert_eq!(val("0xF0u8 | 0x3Cu8"), Typing::UInt8(0xFC));
+    assert_eq!(val("1u8 << 7"), Typing::UInt8(128));
+    assert_eq!(val("128u8 >> 7u64"), Typing::UInt8(1));
+    assert_eq!(val("1u8 >= 2u8"), Typing::Boolean(false));
+    assert_eq!(val("1u8 != 2u8"), Typing::Boolean(true));
+}
+
+#[test]
+fn test_float64() {
+    assert_eq!(val("1.5 * 2.0"), Typing::Float64(3.0));
+    assert_eq!(val("1.0 / 4.0"), Typing::Float64(0.25));
+    assert_eq!(val("5.5 % 2.0"), Typing::Float64(1.5));
+    assert_eq!(val("1.25 + 1.25"), Typing::Float64(2.5));
+    assert_eq!(val("1.0 - 1.5"), Typing::Float64(-0.5));
+    assert_eq!(val("1.0 == 1.0"), Typing::Boolean(true));
+    assert_eq!(val("1.0 > 1.5"), Typing::Boolean(false));
+    assert_eq!(
+        mismatch("1.0 & 1.0"),
+        (
+            BinaryOperation::BitwiseAnd,
+            RawTyping::Float64,
+            RawTyping::Float64
+        )
+    );
+    assert_eq!(
+        mismatch("1.0 << 1"),
+        (
+            BinaryOperation::BitwiseShiftLeft,
+            RawTyping::Float64,
+            RawTyping::Int64
+        )
+    );
+    assert_eq!(
+        mismatch("1 >> 1.0"),
+        (
+            BinaryOperation::BitwiseShiftRight,
+            RawTyping::Int64,
+            RawTyping::Float64
+        )
+    );
+}
+
+#[test]
+fn test_mixed_float() {
+    assert_eq!(val("1 + 0.5"), Typing::Float64(1.5));
+    assert_eq!(val("0.5 + 1u64"), Typing::Float64(1.5));
+    assert_eq!(val("2u8 * 0.25"), Typing::Float64(0.5));
+    assert_eq!(val("255u8 == 255.0"), Typing::Boolean(true));
+    assert_eq!(
+        mismatch("1.0 + true"),
+        (BinaryOperation::Add, RawTyping::Float64, RawTyping::Boolean)
+    );
+}
+
+#[test]
+fn test_mixed_int() {
+    assert_eq!(
+        mismatch("1 + 1u8"),
+        (BinaryOperation::Add, RawTyping::Int64, RawTyping::UInt8)
+    );
+    assert_eq!(
+        mismatch("1u64 == 1"),
+        (BinaryOperation::Equal, RawTyping::UInt64, RawTyping::Int64)
+    );
+    assert_eq!(
+        mismatch("1u8 | 1u64"),
+        (
+            BinaryOperation::BitwiseOr,
+            RawTyping::UInt8,
+            RawTyping::UInt64
+        )
+    );
+}
+
+#[test]
+fn test_boolean() {
+    assert_eq!(val("true && false"), Typing::Boolean(false));
+    assert_eq!(val("true && true"), Typing::Boolean(true));
+    assert_eq!(val("true ^^ true"), Typing::Boolean(false));
+    assert_eq!(val("true ^^ false"), Typing::Boolean(true));
+    assert_eq!(val("false || false"), Typing::Boolean(false));
+    assert_eq!(val("false || true"), Typing::Boolean(true));
+    assert_eq!(val("true & false"), Typing::Boolean(false));
+    assert_eq!(val("true ^ false"), Typing::Boolean(true));
+    assert_eq!(val("false | true"), Typing::Boolean(true));
+    assert_eq!(val("true == true"), Typing::Boolean(true));
+    assert_eq!(val("false < true"), Typing::Boolean(true));
+    assert_eq!(
+        mismatch("true + true"),
+        (BinaryOperation::Add, RawTyping::Boolean, RawTyping::Boolean)
+    );
+    assert_eq!(
+        mismatch("1 && true"),
+        (
+            BinaryOperation::BooleanAnd,
+            RawTyping::Int64,
+            RawTyping::Boolean
+        )
+    );
+    assert_eq!(
+        mismatch("false || 1"),
+        (
+            BinaryOperation::BooleanOr,
+            RawTyping::Boolean,
+            RawTyping::Int64
+        )
+    );
+}
+
+#[test]
+fn test_short_circuit() {
+    // The right side would be a type error if it ran.
+    assert_eq!(val("false && -true"), Typing::Boolean(false));
+    assert_eq!(val("true || -true"), Typing::Boolean(true));
+    assert!(matches!(
+        run_err("false & -true"),
+        RuntimeError::UnaryTypeMismatch { .. }
+    ));
+    assert!(matches!(
+        run_err("true ^^ -true"),
+        RuntimeError::UnaryTypeMismatch { .. }
+    ));
+}
+
+#[test]
+fn test_character() {
+    assert_eq!(val("'a' == 'a'"), Typing::Boolean(true));
+    assert_eq!(val("'a' != 'b'"), Typing::Boolean(true));
+    assert_eq!(val("'a' < 'b'"), Typing::Boolean(true));
+    assert_eq!(val("'z' <= 'a'"), Typing::Boolean(false));
+    assert_eq!(
+        mismatch("'a' + 'b'"),
+        (
+            BinaryOperation::Add,
+            RawTyping::Character,
+            RawTyping::Character
+        )
+    );
+    assert_eq!(
+        mismatch("'a' == 97"),
+        (
+            BinaryOperation::Equal,
+            RawTyping::Character,
+            RawTyping::Int64
+        )
+    );
+}
+
+#[test]
+fn test_string() {
+    assert_eq!(val(r#""abc" == "abc""#), Typing::Boolean(true));
+    assert_eq!(val(r#""abc" != "abd""#), Typing::Boolean(true));
+    assert_eq!(val(r#""abc" < "abd""#), Typing::Boolean(true));
+    assert_eq!(
+        mismatch(r#""a" + "b""#),
+        (BinaryOperation::Add, RawTyping::String, RawTyping::String)
+    );
+}
+
+#[test]
+fn test_mismatch_error() {
+    assert_eq!(
+        run_err("1 + 2 * true"),
+        RuntimeError::BinaryTypeMismatch {
+            op: BinaryOperation::Multiply,
+            lhs: RawTyping::Int64,
+            rhs: RawTyping::Boolean,
+            pos: Pos::new(1, 7),
+            span: Span::new(4, 12),
+        }
+    );
+    assert_eq!(
+        run_err("1 + 2 * true").to_string(),
+        "Runtime: Can not apply '*' to i64 and bool at (1:7)."
+    );
+}
+
+#[test]
+fn test_overflow() {
+    let overflow = |contents| match run_err(contents) {
+        RuntimeError::Overflow { ty, .. } => ty,
+        err => panic!("Expected Overflow, got {err:?}"),
+    };
+    assert_eq!(overflow("9223372036854775807 + 1"), RawTyping::Int64);
+    assert_eq!(overflow("0 - 9223372036854775807 - 2"), RawTyping::Int64);
+    assert_eq!(overflow("4611686018427387904 * 2"), RawTyping::Int64);
+    assert_eq!(
+        overflow("(0 - 9223372036854775807 - 1) / -1"),
+        RawTyping::Int64
+    );
+    assert_eq!(
+        overflow("(0 - 9223372036854775807 - 1) % -1"),
+        RawTyping::Int64
+    );
+    assert_eq!(overflow("-(0 - 9223372036854775807 - 1)"), RawTyping::Int64);
+    assert_eq!(overflow("18446744073709551615 + 1u64"), RawTyping::UInt64);
+    assert_eq!(overflow("0u64 - 1u64"), RawTyping::UInt64);
+    assert_eq!(overflow("255u8 + 1u8"), RawTyping::UInt8);
+    assert_eq!(overflow("16u8 * 16u8"), RawTyping::UInt8);
+    assert_eq!(val("9223372036854775806 + 1"), Typing::Int64(i64::MAX));
+    assert_eq!(val("254u8 + 1u8"), Typing::UInt8(255));
+    assert_eq!(val("-9223372036854775808"), Typing::Int64(i64::MIN));
+    assert_eq!(
+        val("-9_223_372_036_854_775_808i64"),
+        Typing::Int64(i64::MIN)
+    );
+    assert_eq!(
+        val("-0x8000_0000_0000_0000 + 1"),
+        Typing::Int64(i64::MIN + 1)
+    );
+    assert_eq!(overflow("--9223372036854775808"), RawTyping::Int64);
+
+    assert_eq!(
+        run_err("1 + 255u8 + 1u8").to_string(),
+        "Runtime: Can not apply '+' to i64 and u8 at (1:3)."
+    );
+    assert_eq!(
+        run_err("(255u8 + 1u8) + 1u8").to_string(),
+        "Runtime: Arithmetic overflow of u8 at (1:8)."
+    );
+}
+
+#[test]
+fn test_divide_by_zero() {
+    assert_eq!(
+        run_err("1 / 0"),
+        RuntimeError::DivideByZero {
+            pos: Pos::new(1, 3),
+            span: Span::new(0, 5),
+        }
+    );
+    assert_eq!(
+        run_err("1 % 0"),
+        RuntimeError::DivideByZero {
+            pos: Pos::new(1, 3),
+            span: Span::new(0, 5),
+        }
+    );
+    assert_eq!(
+        run_err("1u64 / 0u64"),
+        RuntimeError::DivideByZero {
+            pos: Pos::new(1, 6),
+            span: Span::new(0, 11),
+        }
+    );
+    assert_eq!(
+        run_err("1u8 % 0u8"),
+        RuntimeError::DivideByZero {
+            pos: Pos::new(1, 5),
+            span: Span::new(0, 9),
+        }
+    );
+    assert_eq!(
+        run_err("1 / 0").to_string(),
+        "Runtime: Divide by zero at (1:3)."
+    );
+}
+
+#[test]
+fn test_shift_out_of_range() {
+    let shift = |contents| match run_err(contents) {
+        RuntimeError::ShiftOutOfRange { amount, ty, .. } => (amount, ty),
+        err => panic!("Expected ShiftOutOfRange, got {err:?}"),
+    };
+    assert_eq!(shift("1 << 64"), (64, RawTyping::Int64));
+    assert_eq!(shift("1 >> -1"), (-1, RawTyping::Int64));
+    assert_eq!(
+        shift("1u64 << 18446744073709551615"),
+        (18_446_744_073_709_551_615, RawTyping::UInt64)
+    );
+    assert_eq!(shift("1u8 << 8"), (8, RawTyping::UInt8));
+    assert_eq!(val("1 << 63"), Typing::Int64(i64::MIN));
+    assert_eq!(val("1u8 << 7u8"), Typing::UInt8(128));
+    assert_eq!(val("255u8 >> 7"), Typing::UInt8(1));
+    assert_eq!(
+        run_err("1u8 << 8").to_string(),
+        "Runtime: Can not shift u8 by 8 at (1:5), the amount must be at least 0 and less than the bits in u8."
+    );
+}
+
+#[test]
+fn test_wrapping_saturating() {
+    let (pos, span) = (Pos::new(1, 1), Span::new(0, 1));
+    assert_eq!(
+        wrapping_op(
+            BinaryOperation::Add,
+            Typing::UInt8(255),
+            Typing::UInt8(1),
+            pos,
+            span
+        ),
+        Ok(Typing::UInt8(0))
+    );
+    assert_eq!(
+        wrapping_op(
+            BinaryOperation::Subtract,
+            Typing::Int64(i64::MIN),
+            Typing::Int64(1),
+            pos,
+            span
+        ),
+        Ok(Typing::Int64(i64::MAX))
+    );
+    assert_eq!(
+        wrapping_op(
+            BinaryOperation::Multiply,
+            Typing::UInt64(u64::MAX),
+            Typing::UInt64(2),
+            pos,
+            span
+        ),
+        Ok(Typing::UInt64(u64::MAX - 1))
+    );
+    assert_eq!(
+        saturating_op(
+            BinaryOperation::Add,
+            Typing::UInt8(255),
+            Typing::UInt8(1),
+            pos,
+            span
+        ),
+        Ok(Typing::UInt8(255))
+    );
+    assert_eq!(
+        saturating_op(
+            BinaryOperation::Subtract,
+            Typing::UInt64(0),
+            Typing::UInt64(1),
+            pos,
+            span
+        ),
+        Ok(Typing::UInt64(0))
+    );
+    assert_eq!(
+        saturating_op(
+            BinaryOperation::Multiply,
+            Typing::Int64(i64::MIN),
+            Typing::Int64(2),
+            pos,
+            span
+        ),
+        Ok(Typing::Int64(i64::MIN))
+    );
+    assert_eq!(
+        wrapping_op(
+            BinaryOperation::Divide,
+            Typing::UInt8(1),
+            Typing::UInt8(0),
+            pos,
+            span
+        ),
+        Err(RuntimeError::DivideByZero { pos, span })
+    );
+    assert_eq!(
+        saturating_op(
+            BinaryOperation::Less,
+            Typing::UInt8(1),
+            Typing::UInt8(2),
+            pos,
+            span
+        ),
+        Ok(Typing::Boolean(true))
+    );
+}
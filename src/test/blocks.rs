@@ -0,0 +1,110 @@
+use crate::{
+    data::{Token, Typing},
+    test::{parse_err, run, run_err},
+    ParseError, Pos, RuntimeError, Span,
+};
+
+#[test]
+fn test_statements() {
+    assert_eq!(run("1; 2; 3"), Some(Typing::Int64(3)));
+    assert_eq!(run("1; 2; 3;"), None);
+    assert_eq!(run(""), None);
+    assert_eq!(run(";;1;;"), None);
+    assert_eq!(
+        run("let width = 4;\nlet height = 3;\nwidth * height\n"),
+        Some(Typing::Int64(12))
+    );
+    assert_eq!(run("var x = 1; x += 2; x *= 3; x"), Some(Typing::Int64(9)));
+    assert_eq!(run("let x = 1"), None);
+
+    assert_eq!(
+        parse_err("let x = 1\nx"),
+        ParseError::ExpectedToken {
+            expected: Token::SemiColon,
+            found: Token::Ident("x".to_string()),
+            pos: Pos::new(2, 1),
+            span: Span::new(10, 11),
+        }
+    );
+    assert_eq!(
+        run_err("let x = 1; let x = 2"),
+        RuntimeError::Redeclared {
+            id: "x".to_string(),
+            pos: Pos::new(1, 16),
+            span: Span::new(11, 20),
+        }
+    );
+}
+
+#[test]
+fn test_blocks() {
+    assert_eq!(run("{ 1 + 2 }"), Some(Typing::Int64(3)));
+    assert_eq!(run("{ 1 + 2; }"), None);
+    assert_eq!(run("{}"), None);
+    assert_eq!(run("{ { 4 } } * 2"), Some(Typing::Int64(8)));
+    assert_eq!(
+        run("let area = { let width = 4; width * 3 }; area"),
+        Some(Typing::Int64(12))
+    );
+
+    // Blocks do not need a `;` to be a statement.
+    assert_eq!(
+        run("var x = 1; { x += 1 } { x += 1 } x"),
+        Some(Typing::Int64(3))
+    );
+
+    // Shadowing ends with the block, assignments to outer variables stay.
+    assert_eq!(
+        run("let x = 1; var y = 0; { let x = 10; y = x; } x + y"),
+        Some(Typing::Int64(11))
+    );
+    assert_eq!(
+        run_err("{ let inner = 1; } inner"),
+        RuntimeError::UndefinedVariable {
+            id: "inner".to_string(),
+            pos: Pos::new(1, 20),
+            span: Span::new(19, 24),
+            suggestions: Vec::new()
+        }
+    );
+    assert_eq!(
+        run_err("{ let x = 1; let x = 2; }"),
+        RuntimeError::Redeclared {
+            id: "x".to_string(),
+            pos: Pos::new(1, 18),
+            span: Span::new(13, 22),
+        }
+    );
+    assert_eq!(
+        run_err("1 + { 2; }"),
+        RuntimeError::ExpectedValue {
+            pos: Pos::new(1, 5),
+            span: Span::new(4, 10),
+        }
+    );
+
+    assert_eq!(
+        parse_err("{ 1 + 2"),
+        ParseError::ExpectedToken {
+            expected: Token::RCurly,
+            found: Token::Eof,
+            pos: Pos::new(1, 8),
+            span: Span::new(7, 7),
+        }
+    );
+    assert!(matches!(
+        parse_err("{ 1 2 }"),
+        ParseError::ExpectedToken {
+            expected: Token::SemiColon,
+            ..
+        }
+    ));
+    assert!(matches!(
+        parse_err("1 }"),
+        ParseError::ExpectedToken {
+            expected: Token::SemiColon,
+            found: Token::RCurly,
+            ..
+        }
+    ));
+}
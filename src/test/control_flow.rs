@@ -0,0 +1,322 @@
+use crate::{
+    data::{RawTyping, Token, Typing},
+    test::{parse_err, run, run_err},
+    ParseError, Pos, RuntimeError, Span,
+};
+
+#[test]
+fn test_if() {
+    assert_eq!(run("if true { 1 } else { 2 }"), Some(Typing::Int64(1)));
+    assert_eq!(run("if 1 > 2 { 1 } else { 2 }"), Some(Typing::Int64(2)));
+    assert_eq!(
+        run("let x = 5; if x < 0 { 'n' } else if x == 0 { 'z' } else { 'p' }"),
+        Some(Typing::Character('p'))
+    );
+    assert_eq!(
+        run("let x = 0; if x < 0 { 'n' } else if x == 0 { 'z' } else { 'p' }"),
+        Some(Typing::Character('z'))
+    );
+    assert_eq!(
+        run("let x = if false { 1 } else { 2 } * 10; x"),
+        Some(Typing::Int64(20))
+    );
+
+    // Without else there is no value, but the branch still runs.
+    assert_eq!(run("if true { 1 }"), None);
+    assert_eq!(
+        run("var x = 1; if true { x = 2 } x"),
+        Some(Typing::Int64(2))
+    );
+    assert_eq!(
+        run("var x = 1; if false { x = 2 } x"),
+        Some(Typing::Int64(1))
+    );
+    assert_eq!(
+        run("var x = 0; if x == 0 { x += 1 } else if x == 1 { x += 10 } x"),
+        Some(Typing::Int64(1))
+    );
+
+    // Branches are scopes.
+    assert_eq!(
+        run("let x = 1; if true { let x = 2; x } else { 0 }"),
+        Some(Typing::Int64(2))
+    );
+    assert!(matches!(
+        run_err("if true { let y = 1; } y"),
+        RuntimeError::UndefinedVariable { .. }
+    ));
+}
+
+#[test]
+fn test_if_errors() {
+    assert_eq!(
+        run_err("if 1 { 2 }"),
+        RuntimeError::ConditionNotBoolean {
+            found: RawTyping::Int64,
+            pos: Pos::new(1, 4),
+            span: Span::new(3, 4),
+        }
+    );
+    assert_eq!(
+        run_err("if false { 1 } else if \"yes\" { 2 }"),
+        RuntimeError::ConditionNotBoolean {
+            found: RawTyping::String,
+            pos: Pos::new(1, 24),
+            span: Span::new(23, 28),
+        }
+    );
+    assert_eq!(
+        run_err("if 1 { 2 }").to_string(),
+        "Runtime: Condition must be a bool but was i64 at (1:4)."
+    );
+    assert_eq!(
+        run_err("1 + if false { 1 }"),
+        RuntimeError::ExpectedValue {
+            pos: Pos::new(1, 5),
+            span: Span::new(4, 18),
+        }
+    );
+
+    assert_eq!(
+        parse_err("if true 1"),
+        ParseError::ExpectedToken {
+            expected: Token::LCurly,
+            found: Token::NumberDecimal("1".to_string(), None),
+            pos: Pos::new(1, 9),
+            span: Span::new(8, 9),
+        }
+    );
+    assert!(matches!(
+        parse_err("if true { 1 } else 2"),
+        ParseError::ExpectedToken {
+            expected: Token::LCurly,
+            ..
+        }
+    ));
+    assert!(matches!(
+        parse_err("if { 1 }"),
+        ParseError::ExpectedToken {
+            expected: Token::LCurly,
+            found: Token::Eof,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_return() {
+    assert_eq!(run("ret 1; 2"), Some(Typing::Int64(1)));
+    assert_eq!(run("ret; 2"), None);
+    assert_eq!(run("ret"), None);
+    assert_eq!(
+        run("var x = 1; { x = 2; ret x + 1 } x = 10"),
+        Some(Typing::Int64(3))
+    );
+    assert_eq!(
+        run("let x = 3; if x > 2 { ret 'b' } 's'"),
+        Some(Typing::Character('b'))
+    );
+}
+
+#[test]
+fn test_guard() {
+    let check = |x: i64| {
+        run(&format!(
+            "let x = {x}; guard x > 0, x < 10 else {{ ret false }} true"
+        ))
+    };
+    assert_eq!(check(5), Some(Typing::Boolean(true)));
+    assert_eq!(check(0), Some(Typing::Boolean(false)));
+    assert_eq!(check(10), Some(Typing::Boolean(false)));
+
+    // Names declared in the guard stay in scope.
+    assert_eq!(
+        run("let s = 4; guard let half = s / 2, half * 2 == s else { ret } half"),
+        Some(Typing::Int64(2))
+    );
+    assert_eq!(
+        run("let s = 5; guard let half = s / 2, half * 2 == s else { ret -1 } half"),
+        Some(Typing::Int64(-1))
+    );
+    assert_eq!(
+        run("guard true else { if true { ret 1 } else { ret 2 } } 3"),
+        Some(Typing::Int64(3))
+    );
+
+    assert_eq!(
+        run_err("guard 1 else { ret }"),
+        RuntimeError::ConditionNotBoolean {
+            found: RawTyping::Int64,
+            pos: Pos::new(1, 7),
+            span: Span::new(6, 7),
+        }
+    );
+    assert!(matches!(
+        run_err("let x = 1; guard let x = 2 else { ret }"),
+        RuntimeError::Redeclared { .. }
+    ));
+}
+
+#[test]
+fn test_guard_errors() {
+    assert_eq!(
+        parse_err("guard true else { 1 }"),
+        ParseError::GuardNotDiverging {
+            pos: Pos::new(1, 17),
+            span: Span::new(16, 21),
+        }
+    );
+    assert!(matches!(
+        parse_err("guard true else { if true { ret } }"),
+        ParseError::GuardNotDiverging { .. }
+    ));
+    assert!(matches!(
+        parse_err("guard true else { if true { ret } else { 1 } }"),
+        ParseError::GuardNotDiverging { .. }
+    ));
+    assert_eq!(
+        parse_err("guard true { ret }"),
+        ParseError::ExpectedToken {
+            expected: Token::Else,
+            found: Token::LCurly,
+            pos: Pos::new(1, 12),
+            span: Span::new(11, 12),
+        }
+    );
+    assert_eq!(
+        parse_err("guard true else { 1 }").to_string(),
+        "Parser: The else block of guard at (1:17) must end the scope with ret, break or continue."
+    );
+}
+
+#[test]
+fn test_while() {
+    assert_eq!(
+        run("var i = 0; var sum = 0; while i < 5 { i += 1; sum += i } sum"),
+        Some(Typing::Int64(15))
+    );
+    assert_eq!(run("while false { 1 }"), None);
+    assert_eq!(
+        run("var i = 0; while true { i += 1; if i == 3 { break } } i"),
+        Some(Typing::Int64(3))
+    );
+    assert_eq!(
+        run("var i = 0; var odd = 0; while i < 6 { i += 1; if i % 2 == 0 { continue } odd += 1 } odd"),
+        Some(Typing::Int64(3))
+    );
+
+    // A loop is an expression with the value of break.
+    assert_eq!(
+        run("var i = 1; let found = while true { i *= 3; if i > 20 { break i } }; found"),
+        Some(Typing::Int64(27))
+    );
+    assert_eq!(run("var i = 0; while i < 3 { i += 1 }"), None);
+
+    // Every iteration has its own frame.
+    assert_eq!(
+        run("var i = 0; while i < 3 { let x = i; i += 1 } i"),
+        Some(Typing::Int64(3))
+    );
+    assert!(matches!(
+        run_err("var i = 0; while i < 1 { let x = i; i += 1 } x"),
+        RuntimeError::UndefinedVariable { .. }
+    ));
+
+    assert_eq!(
+        run_err("while 1 { }"),
+        RuntimeError::ConditionNotBoolean {
+            found: RawTyping::Int64,
+            pos: Pos::new(1, 7),
+            span: Span::new(6, 7),
+        }
+    );
+    assert_eq!(
+        run("var i = 0; while true { i += 1; guard i < 4 else { break } } i"),
+        Some(Typing::Int64(4))
+    );
+    assert_eq!(
+        run("var i = 0; while true { i += 1; if i == 2 { ret i * 10 } } 0"),
+        Some(Typing::Int64(20))
+    );
+}
+
+#[test]
+fn test_while_labels() {
+    let pairs = "
+        var pairs = 0;
+        var i = 0;
+        'outer: while i < 4 {
+            i += 1;
+            var j = 0;
+            while j < 4 {
+                j += 1;
+                if j > i { continue 'outer }
+                if i == 3 { break 'outer }
+                pairs += 1;
+            }
+        }
+        pairs
+    ";
+    assert_eq!(run(pairs), Some(Typing::Int64(3)));
+    assert_eq!(
+        run("'a: while true { while true { break 'a 7 } }"),
+        Some(Typing::Int64(7))
+    );
+    assert_eq!(
+        run("var n = 0; 'a: while n < 2 { n += 1; 'a: while true { break 'a } } n"),
+        Some(Typing::Int64(2))
+    );
+
+    let tokens: Vec<Token> = crate::lexer::lex("'outer: 'a' 'b: 'é'")
+        .unwrap()
+        .into_iter()
+        .map(|t| t.token)
+        .collect();
+    assert_eq!(
+        tokens,
+        [
+            Token::Label("outer".to_string()),
+            Token::Colon,
+            Token::Character('a'),
+            Token::Label("b".to_string()),
+            Token::Colon,
+            Token::Character('é'),
+            Token::Eof
+        ]
+    );
+}
+
+#[test]
+fn test_while_errors() {
+    assert_eq!(
+        parse_err("break"),
+        ParseError::OutsideLoop {
+            found: Token::Break,
+            pos: Pos::new(1, 1),
+            span: Span::new(0, 5),
+        }
+    );
+    assert_eq!(
+        parse_err("while true { } continue").to_string(),
+        "Parser: 'continue' at (1:16) is not inside of a loop."
+    );
+    assert!(matches!(
+        parse_err("while break { }"),
+        ParseError::OutsideLoop { .. }
+    ));
+    assert_eq!(
+        parse_err("'a: while true { while true { break 'b } }"),
+        ParseError::UndefinedLabel {
+            label: "b".to_string(),
+            pos: Pos::new(1, 37),
+            span: Span::new(36, 38),
+        }
+    );
+    assert!(matches!(
+        parse_err("'a: 1"),
+        ParseError::ExpectedToken {
+            expected: Token::While,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,200 @@
+use crate::{
+    diagnostics::{render, Diagnostic},
+    lexer::lex,
+    parser::parse,
+    Interpreter, Pos, RuntimeError, Span,
+};
+
+fn render_err(source: &str) -> String {
+    let err = lex(source)
+        .and_then(parse)
+        .and_then(|ast| Ok(Interpreter::new(ast).run()?))
+        .err()
+        .unwrap();
+    render(source, &err, false)
+}
+
+#[test]
+fn test_lexer() {
+    assert_eq!(
+        render_err("1 + 0b102"),
+        "\
+error: Lexer: Invalid digit '2' for a base 2 number at (1:9).
+ --> (1:9)
+  |
+1 | 1 + 0b102
+  |         ^ not a base 2 digit
+"
+    );
+    assert_eq!(
+        render_err("let x = 1 $ 2"),
+        "\
+error: Lexer: Unexpected character '$' at (1:11).
+ --> (1:11)
+  |
+1 | let x = 1 $ 2
+  |           ^ not part of any token
+"
+    );
+}
+
+#[test]
+fn test_parser() {
+    assert_eq!(
+        render_err("1 < 2\n\n\n\t< 3"),
+        "\
+error: Parser: '<' at (4:2) can not be chained with '<' at (1:3), add parentheses like `(1 < 2) < 3`.
+ --> (4:2)
+  |
+1 | 1 < 2
+  |   - with this '<'
+...
+4 | \t< 3
+  | \t^ can not be chained
+  |
+  = help: add parentheses like `(1 < 2) < 3`
+"
+    );
+    assert_eq!(
+        render_err("(1 + 2"),
+        "\
+error: Parser: Expected ')' but found end of file at (1:7).
+ --> (1:7)
+  |
+1 | (1 + 2
+  |       ^ expected ')'
+"
+    );
+    assert_eq!(
+        render_err("1 == 2 != 3"),
+        "\
+error: Parser: '!=' at (1:8) can not be chained with '==' at (1:3), add parentheses like `(1 == 2) != 3`.
+ --> (1:8)
+  |
+1 | 1 == 2 != 3
+  |   -- with this '=='
+  |        ^^ can not be chained
+  |
+  = help: add parentheses like `(1 == 2) != 3`
+"
+    );
+    assert_eq!(
+        render_err("let x: number = 1"),
+        "\
+error: Parser: Unknown type 'number' at (1:8).
+ --> (1:8)
+  |
+1 | let x: number = 1
+  |        ^^^^^^ unknown type
+  |
+  = help: use one of i64, int, u64, uint, f64, float, u8, byte, bool, char, string or range
+"
+    );
+}
+
+#[test]
+fn test_runtime() {
+    assert_eq!(
+        render_err("1 +\n  2 * true"),
+        "\
+error: Runtime: Can not apply '*' to i64 and bool at (2:5).
+ --> (2:5)
+  |
+2 |   2 * true
+  |   ^^^^^^^^ i64 and bool
+"
+    );
+    assert_eq!(
+        render_err("let value = 1;\nvaleu + 1"),
+        "\
+error: Runtime: Undefined variable 'valeu' at (2:1), did you mean 'value'?
+ --> (2:1)
+  |
+2 | valeu + 1
+  | ^^^^^ undefined variable
+  |
+  = help: a variable with a similar name exists: 'value'
+"
+    );
+
+    assert_eq!(
+        render_err("fn f(x: int) -> int { x }\nf(1, 2)"),
+        "\
+error: Runtime: 'f' takes 1 arguments but was given 2 at (2:1).
+ --> (2:1)
+  |
+2 | f(1, 2)
+  | ^^^^^^^ expected 1 arguments, found 2
+"
+    );
+
+    let err = RuntimeError::DivideByZero {
+        pos: Pos::new(2, 7),
+        span: Span::new(13, 18),
+    }
+    .in_function("foo".to_string(), Pos::new(12, 1), Span::new(29, 34));
+    let source = "fn foo() {\n  1 / 0\n}\n\n\n\n\n\n\n\n\nfoo()";
+    assert_eq!(
+        Diagnostic::from(&err).render(source, false),
+        "\
+error: Runtime: Divide by zero at (2:7).
+  --> (2:7)
+   |
+ 2 |   1 / 0
+   |   ^^^^^ divided by zero
+...
+12 | foo()
+   | ----- in fn foo called here
+   |
+   = note: in fn foo called from (12:1)
+"
+    );
+}
+
+#[test]
+fn test_color() {
+    let rendered = Diagnostic::new("bad")
+        .with_label(Pos::new(1, 1), "here")
+        .render("x", true);
+    assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m"));
+    assert!(rendered.contains("\x1b[1;31m^ here\x1b[0m"));
+
+    let rendered = Diagnostic::new("bad")
+        .with_label(Pos::new(1, 1), "here")
+        .render("x", false);
+    assert!(!rendered.contains('\x1b'));
+}
+
+#[test]
+fn test_out_of_source() {
+    assert_eq!(
+        Diagnostic::new("bad")
+            .with_label(Pos::new(usize::MAX, usize::MAX), "end")
+            .with_note("a note")
+            .render("ab\ncd", false),
+        "\
+error: bad
+ --> (2:3)
+  |
+2 | cd
+  |   ^ end
+  |
+  = note: a note
+"
+    );
+    assert_eq!(Diagnostic::new("bad").render("", false), "error: bad\n");
+
+    // Underlines stop at the end of the line.
+    assert_eq!(
+        Diagnostic::new("bad")
+            .with_label_span(Pos::new(1, 2), Span::new(1, 10), "long")
+            .render("abc", false),
+        "\
+error: bad
+ --> (1:2)
+  |
+1 | abc
+  |  ^^ long
+"
+    );
+}
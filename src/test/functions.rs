@@ -0,0 +1,296 @@
+use crate::{
+    data::{RawTyping, Token, Typing},
+    interpreter::MAX_CALL_DEPTH,
+    lexer::lex,
+    parser::parse,
+    test::{parse_err, run, run_err},
+    Interpreter, ParseError, Pos, RuntimeError, Span,
+};
+
+#[test]
+fn test_functions() {
+    assert_eq!(
+        run("fn add(a: int, b: int) -> int { a + b } add(1, 2)"),
+        Some(Typing::Int64(3))
+    );
+    assert_eq!(
+        run("fn sign(x: i64) -> char { if x < 0 { ret '-' } '+' } sign(-5)"),
+        Some(Typing::Character('-'))
+    );
+    assert_eq!(
+        run("fn answer() -> u8 { 42u8 } answer() + answer()"),
+        Some(Typing::UInt8(84))
+    );
+    assert_eq!(
+        run("fn greet(name: string,) -> string { \"hi {name}\" } greet(\"kot\",)"),
+        Some(Typing::String(Box::new("hi kot".into())))
+    );
+
+    // Without a return type calls have no value.
+    assert_eq!(
+        run("var n = 0; fn bump() { n += 1 } bump(); bump(); n"),
+        Some(Typing::Int64(2))
+    );
+    assert_eq!(run("fn nothing() { 1 } nothing()"), None);
+    assert_eq!(run("fn early() { ret } early()"), None);
+
+    // Functions can be called before they are declared, and from inside of loops.
+    assert_eq!(
+        run("var sum = 0; for i in 0..<4 { sum += square(i) } fn square(x: int) -> int { x * x } sum"),
+        Some(Typing::Int64(14))
+    );
+    assert_eq!(
+        run("fn f() -> int { while true { ret 5 } 0 } f()"),
+        Some(Typing::Int64(5))
+    );
+    assert_eq!(
+        run("let f = { fn inner() -> int { 7 } inner }; f()"),
+        Some(Typing::Int64(7))
+    );
+}
+
+#[test]
+fn test_recursion() {
+    assert_eq!(
+        run("fn fib(n: int) -> int { if n < 2 { ret n } fib(n - 1) + fib(n - 2) } fib(15)"),
+        Some(Typing::Int64(610))
+    );
+    assert_eq!(
+        run("
+            fn is_even(n: u64) -> bool { if n == 0u64 { true } else { is_odd(n - 1u64) } }
+            fn is_odd(n: u64) -> bool { if n == 0u64 { false } else { is_even(n - 1u64) } }
+            is_even(10u64)
+        "),
+        Some(Typing::Boolean(true))
+    );
+    // A nested function can call itself.
+    assert_eq!(
+        run(
+            "{ fn count(n: int) -> int { guard n > 0 else { ret 0 } 1 + count(n - 1) } count(20) }"
+        ),
+        Some(Typing::Int64(20))
+    );
+    // Functions declared in the same block can call each other.
+    assert_eq!(
+        run("{ fn a() -> int { b() } fn b() -> int { 1 } a() }"),
+        Some(Typing::Int64(1))
+    );
+
+    let depth = "fn depth(n: int) -> int { if n == 0 { 0 } else { 1 + depth(n - 1) } }";
+    assert_eq!(
+        run(&format!("{depth} depth({})", MAX_CALL_DEPTH - 1)),
+        Some(Typing::Int64(MAX_CALL_DEPTH as i64 - 1))
+    );
+    assert_eq!(
+        run_err("fn forever(n: int) -> int { forever(n + 1) } forever(0)"),
+        RuntimeError::CallDepth {
+            function: "forever".to_string(),
+            limit: MAX_CALL_DEPTH,
+            pos: Pos::new(1, 29),
+            span: Span::new(28, 42),
+        }
+    );
+
+    // Hosts can lower the limit.
+    let run_limited = |max_call_depth| {
+        let mut interpreter =
+            Interpreter::new(parse(lex(&format!("{depth} depth(10)")).unwrap()).unwrap());
+        interpreter.max_call_depth = max_call_depth;
+        interpreter.run()
+    };
+    assert_eq!(
+        run_limited(10),
+        Err(RuntimeError::CallDepth {
+            function: "depth".to_string(),
+            limit: 10,
+            pos: Pos::new(1, 54),
+            span: Span::new(53, 65),
+        })
+    );
+    assert_eq!(run_limited(11), Ok(Some(Typing::Int64(10))));
+    assert_eq!(
+        run_limited(10).unwrap_err().to_string(),
+        "Runtime: Calling 'depth' at (1:54) goes deeper than 10 calls."
+    );
+
+    // Expressions around the calls count towards the depth too.
+    let nested = format!(
+        "fn f(n: int) -> int {{ if n == 0 {{ 0 }} else {{ {}f(n - 1){} }} }}",
+        "1 + (".repeat(40),
+        ")".repeat(40)
+    );
+    let run_nested = |n, max_depth| {
+        let mut interpreter =
+            Interpreter::new(parse(lex(&format!("{nested} f({n})")).unwrap()).unwrap());
+        interpreter.max_depth = max_depth;
+        interpreter.run()
+    };
+    assert_eq!(run_nested(2, 200), Ok(Some(Typing::Int64(80))));
+    assert_eq!(
+        run_nested(10, 200),
+        Err(RuntimeError::NestingDepth {
+            limit: 200,
+            pos: Pos::new(1, 136),
+            span: Span::new(135, 136),
+        })
+    );
+    assert_eq!(
+        run_nested(10, 200).unwrap_err().to_string(),
+        "Runtime: Running the expression at (1:136) goes deeper than 200 levels."
+    );
+}
+
+#[test]
+fn test_call_frames() {
+    // The body can see globals, but not the frames of the caller.
+    assert_eq!(
+        run("let base = 10; fn add(x: int) -> int { base + x } add(1)"),
+        Some(Typing::Int64(11))
+    );
+    assert_eq!(
+        run_err("fn get() -> int { local } { let local = 1; get() }"),
+        RuntimeError::UndefinedVariable {
+            id: "local".to_string(),
+            pos: Pos::new(1, 19),
+            span: Span::new(18, 23),
+            suggestions: Vec::new()
+        }
+        .in_function("get".to_string(), Pos::new(1, 44), Span::new(43, 48))
+    );
+    // Params and locals are gone after the call, and params can not be assigned to.
+    assert!(matches!(
+        run_err("fn f(x: int) { let y = x; } f(1); x"),
+        RuntimeError::UndefinedVariable { .. }
+    ));
+    assert!(matches!(
+        run_err("fn f(x: int) { x = 2 } f(1)").root(),
+        RuntimeError::AssignImmutable { .. }
+    ));
+    // The same frame is used for a call every time.
+    assert_eq!(
+        run("fn f(x: int) -> int { let y = x * 2; y } f(1) + f(2)"),
+        Some(Typing::Int64(6))
+    );
+}
+
+#[test]
+fn test_function_errors() {
+    assert_eq!(
+        run_err("fn f(a: int) { } f()"),
+        RuntimeError::ArgumentCount {
+            function: "f".to_string(),
+            expected: 1,
+            found: 0,
+            pos: Pos::new(1, 18),
+            span: Span::new(17, 20),
+        }
+    );
+    assert_eq!(
+        run_err("fn f(a: int) { } f(true)"),
+        RuntimeError::VariableTypeMismatch {
+            id: "a".to_string(),
+            expected: RawTyping::Int64,
+            found: RawTyping::Boolean,
+            pos: Pos::new(1, 20),
+            span: Span::new(19, 23),
+        }
+    );
+    assert_eq!(
+        run_err("fn f() -> int { 'a' } f()"),
+        RuntimeError::ReturnTypeMismatch {
+            function: "f".to_string(),
+            expected: RawTyping::Int64,
+            found: Some(RawTyping::Character),
+            pos: Pos::new(1, 23),
+            span: Span::new(22, 25),
+        }
+    );
+    assert_eq!(
+        run_err("fn f() -> int { ret } f()").to_string(),
+        "Runtime: 'f' returns i64 but ended without a value at (1:23)."
+    );
+    assert_eq!(
+        run_err("let x = 1; x()"),
+        RuntimeError::NotCallable {
+            id: "x".to_string(),
+            found: RawTyping::Int64,
+            pos: Pos::new(1, 12),
+            span: Span::new(11, 14),
+        }
+    );
+    assert!(matches!(
+        run_err("fn f() { } fn f() { }"),
+        RuntimeError::Redeclared { .. }
+    ));
+    assert!(matches!(
+        run_err("fn outer() { inner() } fn inner() { 1 / 0 } outer()"),
+        RuntimeError::InFunction { .. }
+    ));
+    let err = run_err("fn outer() { inner() } fn inner() { 1 / 0 } outer()");
+    assert_eq!(
+        err.trace(),
+        [
+            (&"inner".to_string(), Pos::new(1, 14), Span::new(13, 20)),
+            (&"outer".to_string(), Pos::new(1, 45), Span::new(44, 51))
+        ]
+    );
+    assert_eq!(
+        err.root(),
+        &RuntimeError::DivideByZero {
+            pos: Pos::new(1, 39),
+            span: Span::new(36, 41),
+        }
+    );
+
+    assert_eq!(
+        parse_err("fn f(a: int, a: int) { }"),
+        ParseError::DuplicateParameter {
+            id: "a".to_string(),
+            pos: Pos::new(1, 14),
+            span: Span::new(13, 14),
+        }
+    );
+    assert!(matches!(
+        parse_err("fn f(a) { }"),
+        ParseError::ExpectedToken {
+            expected: Token::Colon,
+            ..
+        }
+    ));
+    assert!(matches!(
+        parse_err("fn f() -> number { }"),
+        ParseError::UnknownType { .. }
+    ));
+    assert_eq!(
+        parse_err("fn f() { ret 5 } f()"),
+        ParseError::UnexpectedReturnValue {
+            function: "f".to_string(),
+            pos: Pos::new(1, 14),
+            span: Span::new(13, 14),
+        }
+    );
+    assert_eq!(
+        parse_err("fn f() -> int { fn g() { ret 1 + 1 } 1 }"),
+        ParseError::UnexpectedReturnValue {
+            function: "g".to_string(),
+            pos: Pos::new(1, 30),
+            span: Span::new(29, 34),
+        }
+    );
+    assert_eq!(run("fn f() { ret } f()"), None);
+    assert_eq!(
+        run("fn f() -> int { fn g() { ret } ret 1 } f()"),
+        Some(Typing::Int64(1))
+    );
+    assert!(matches!(
+        parse_err("while true { fn f() { break } }"),
+        ParseError::OutsideLoop { .. }
+    ));
+    assert!(matches!(
+        parse_err("f(1 2)"),
+        ParseError::ExpectedToken {
+            expected: Token::RParentheses,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,111 @@
+use crate::{
+    data::{RawTyping, Typing, UnaryOperation},
+    test::{run, run_err},
+    Pos, RuntimeError, Span,
+};
+
+#[test]
+fn test_unary_negate() {
+    assert_eq!(run("-5"), Some(Typing::Int64(-5)));
+    assert_eq!(run("--5"), Some(Typing::Int64(5)));
+    assert_eq!(run("-2.5"), Some(Typing::Float64(-2.5)));
+    assert_eq!(
+        run_err("-5u8"),
+        RuntimeError::UnaryTypeMismatch {
+            op: UnaryOperation::Negate,
+            ty: RawTyping::UInt8,
+            pos: Pos::new(1, 1),
+            span: Span::new(0, 4),
+        }
+    );
+    assert_eq!(
+        run_err("1 + -true"),
+        RuntimeError::UnaryTypeMismatch {
+            op: UnaryOperation::Negate,
+            ty: RawTyping::Boolean,
+            pos: Pos::new(1, 5),
+            span: Span::new(4, 9),
+        }
+    );
+}
+
+#[test]
+fn test_unary_boolean_not() {
+    assert_eq!(run("!true"), Some(Typing::Boolean(false)));
+    assert_eq!(run("!!true"), Some(Typing::Boolean(true)));
+    assert_eq!(run("!(1 < 2)"), Some(Typing::Boolean(false)));
+    assert_eq!(
+        run_err("!1"),
+        RuntimeError::UnaryTypeMismatch {
+            op: UnaryOperation::BooleanNot,
+            ty: RawTyping::Int64,
+            pos: Pos::new(1, 1),
+            span: Span::new(0, 2),
+        }
+    );
+}
+
+#[test]
+fn test_unary_bitwise_not() {
+    assert_eq!(run("~0"), Some(Typing::Int64(-1)));
+    assert_eq!(run("~5"), Some(Typing::Int64(-6)));
+    assert_eq!(run("~0u64"), Some(Typing::UInt64(u64::MAX)));
+    assert_eq!(run("~0b1111_0000u8"), Some(Typing::UInt8(0b0000_1111)));
+    assert_eq!(
+        run_err("~true"),
+        RuntimeError::UnaryTypeMismatch {
+            op: UnaryOperation::BitwiseNot,
+            ty: RawTyping::Boolean,
+            pos: Pos::new(1, 1),
+            span: Span::new(0, 5),
+        }
+    );
+    assert_eq!(
+        run_err("~1.5"),
+        RuntimeError::UnaryTypeMismatch {
+            op: UnaryOperation::BitwiseNot,
+            ty: RawTyping::Float64,
+            pos: Pos::new(1, 1),
+            span: Span::new(0, 4),
+        }
+    );
+    assert_eq!(
+        run_err("~'a'").to_string(),
+        "Runtime: Can not apply unary '~' to char at (1:1)."
+    );
+}
+
+#[test]
+fn test_error_trace() {
+    let err = RuntimeError::DivideByZero {
+        pos: Pos::new(3, 5),
+        span: Span::new(20, 25),
+    }
+    .in_function("foo".to_string(), Pos::new(7, 1), Span::new(40, 45))
+    .in_function("bar".to_string(), Pos::new(9, 2), Span::new(60, 65));
+
+    assert_eq!(
+        err.root(),
+        &RuntimeError::DivideByZero {
+            pos: Pos::new(3, 5),
+            span: Span::new(20, 25),
+        }
+    );
+    assert_eq!(err.pos(), Pos::new(3, 5));
+    assert_eq!(err.span(), Span::new(20, 25));
+    assert_eq!(
+        err.trace(),
+        vec![
+            (&"foo".to_string(), Pos::new(7, 1), Span::new(40, 45)),
+            (&"bar".to_string(), Pos::new(9, 2), Span::new(60, 65))
+        ]
+    );
+    assert_eq!(
+        err.to_string(),
+        "Runtime: Divide by zero at (3:5).\n    in fn foo called from (7:1)\n    in fn bar called from (9:2)"
+    );
+
+    let err = run_err("1 + 2 * true");
+    assert_eq!(err.pos(), Pos::new(1, 7));
+    assert!(err.trace().is_empty());
+}
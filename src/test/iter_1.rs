@@ -2,7 +2,7 @@ use crate::{
     data::{Ast, BinaryOperation, PosAst, PosToken, Token, Typing},
     lexer::lex,
     parser::parse,
-    Pos,
+    Pos, Span,
 };
 use std::{fs::read_to_string, ops::Deref};
 
@@ -13,34 +13,49 @@ fn kot_1() {
 
     assert_lexer!(
         [
-            PosToken::new(Token::NumberDecimal("1".to_string()), Pos::new(1, 1)),
-            PosToken::new(Token::MathAdd, Pos::new(1, 3)),
-            PosToken::new(Token::NumberDecimal("2".to_string()), Pos::new(1, 5)),
+            PosToken::new(
+                Token::NumberDecimal("1".to_string(), None),
+                Pos::new(1, 1),
+                Span::new(0, 1)
+            ),
+            PosToken::new(Token::MathAdd, Pos::new(1, 3), Span::new(2, 3)),
+            PosToken::new(
+                Token::NumberDecimal("2".to_string(), None),
+                Pos::new(1, 5),
+                Span::new(4, 5)
+            ),
         ],
         lex
     );
 
     let ast = parse(lex).unwrap();
     if let PosAst {
-        ast: Ast::Root(a),
+        ast: Ast::Root(mut stmts),
         pos,
+        span,
     } = ast
     {
         assert_eq!(pos, Pos::new(0, 0));
+        assert_eq!(span, Span::new(0, 6));
+        assert_eq!(stmts.len(), 1);
 
         if let PosAst {
             ast: Ast::BinOp(BinaryOperation::Add, a1, a2),
             pos,
-        } = *a
+            span,
+        } = stmts.remove(0)
         {
             assert_eq!(pos, Pos::new(1, 3));
+            assert_eq!(span, Span::new(0, 5));
 
             if let PosAst {
                 ast: Ast::Value(Typing::Int64(int)),
                 pos,
+                span,
             } = *a1
             {
                 assert_eq!(pos, Pos::new(1, 1));
+                assert_eq!(span, Span::new(0, 1));
                 assert_eq!(int, 1);
             }
             else {
@@ -50,9 +65,11 @@ fn kot_1() {
             if let PosAst {
                 ast: Ast::Value(Typing::Int64(int)),
                 pos,
+                span,
             } = *a2
             {
                 assert_eq!(pos, Pos::new(1, 5));
+                assert_eq!(span, Span::new(4, 5));
                 assert_eq!(int, 2);
             }
             else {
@@ -75,17 +92,37 @@ fn kot_2() {
 
     assert_lexer!(
         [
-            PosToken::new(Token::LParentheses, Pos::new(1, 9)),
-            PosToken::new(Token::NumberDecimal("1".to_string()), Pos::new(1, 10)),
-            PosToken::new(Token::MathAdd, Pos::new(1, 11)),
-            PosToken::new(Token::NumberDecimal("2".to_string()), Pos::new(1, 12)),
-            PosToken::new(Token::RParentheses, Pos::new(1, 13)),
-            PosToken::new(Token::MathMultiply, Pos::new(1, 15)),
-            PosToken::new(Token::NumberDecimal("3".to_string()), Pos::new(3, 9)),
-            PosToken::new(Token::MathAdd, Pos::new(3, 11)),
-            PosToken::new(Token::NumberDecimal("10".to_string()), Pos::new(3, 13)),
-            PosToken::new(Token::MathMultiply, Pos::new(3, 16)),
-            PosToken::new(Token::NumberDecimal("70".to_string()), Pos::new(3, 17)),
+            PosToken::new(Token::LParentheses, Pos::new(1, 9), Span::new(8, 9)),
+            PosToken::new(
+                Token::NumberDecimal("1".to_string(), None),
+                Pos::new(1, 10),
+                Span::new(9, 10)
+            ),
+            PosToken::new(Token::MathAdd, Pos::new(1, 11), Span::new(10, 11)),
+            PosToken::new(
+                Token::NumberDecimal("2".to_string(), None),
+                Pos::new(1, 12),
+                Span::new(11, 12)
+            ),
+            PosToken::new(Token::RParentheses, Pos::new(1, 13), Span::new(12, 13)),
+            PosToken::new(Token::MathMultiply, Pos::new(1, 15), Span::new(14, 15)),
+            PosToken::new(
+                Token::NumberDecimal("3".to_string(), None),
+                Pos::new(3, 9),
+                Span::new(25, 26)
+            ),
+            PosToken::new(Token::MathAdd, Pos::new(3, 11), Span::new(27, 28)),
+            PosToken::new(
+                Token::NumberDecimal("10".to_string(), None),
+                Pos::new(3, 13),
+                Span::new(29, 31)
+            ),
+            PosToken::new(Token::MathMultiply, Pos::new(3, 16), Span::new(32, 33)),
+            PosToken::new(
+                Token::NumberDecimal("70".to_string(), None),
+                Pos::new(3, 17),
+                Span::new(33, 35)
+            ),
         ],
         lex
     );
@@ -2,7 +2,7 @@ use crate::{
     data::{Ast, BinaryOperation, PosAst, PosToken, Token, Typing},
     lexer::lex,
     parser::parse,
-    Pos,
+    Interpreter, Pos, Span,
 };
 use std::{fs::read_to_string, ops::Deref};
 
@@ -13,24 +13,59 @@ fn kot_1() {
 
     assert_lexer!(
         [
-            PosToken::new(Token::Let, Pos::new(1, 1)),
-            PosToken::new(Token::Ident("item".to_string()), Pos::new(1, 5)),
-            PosToken::new(Token::Colon, Pos::new(1, 9)),
-            PosToken::new(Token::Ident("int".to_string()), Pos::new(1, 11)),
-            PosToken::new(Token::Assign, Pos::new(1, 15)),
-            PosToken::new(Token::NumberDecimal("1".to_string()), Pos::new(1, 17)),
-            PosToken::new(Token::MathAdd, Pos::new(1, 19)),
-            PosToken::new(Token::NumberDecimal("222".to_string()), Pos::new(1, 21)),
-            PosToken::new(Token::MathMultiply, Pos::new(1, 25)),
-            PosToken::new(Token::NumberDecimal("3".to_string()), Pos::new(1, 27)),
-            PosToken::new(Token::MathDivide, Pos::new(1, 29)),
-            PosToken::new(Token::NumberDecimal("7".to_string()), Pos::new(1, 30)),
-            PosToken::new(Token::MathAdd, Pos::new(1, 32)),
-            PosToken::new(Token::NumberDecimal("1".to_string()), Pos::new(1, 33)),
+            PosToken::new(Token::Let, Pos::new(1, 1), Span::new(0, 3)),
+            PosToken::new(
+                Token::Ident("item".to_string()),
+                Pos::new(1, 5),
+                Span::new(4, 8)
+            ),
+            PosToken::new(Token::Colon, Pos::new(1, 9), Span::new(8, 9)),
+            PosToken::new(
+                Token::Ident("int".to_string()),
+                Pos::new(1, 11),
+                Span::new(10, 13)
+            ),
+            PosToken::new(Token::Assign, Pos::new(1, 15), Span::new(14, 15)),
+            PosToken::new(
+                Token::NumberDecimal("1".to_string(), None),
+                Pos::new(1, 17),
+                Span::new(16, 17)
+            ),
+            PosToken::new(Token::MathAdd, Pos::new(1, 19), Span::new(18, 19)),
+            PosToken::new(
+                Token::NumberDecimal("222".to_string(), None),
+                Pos::new(1, 21),
+                Span::new(20, 23)
+            ),
+            PosToken::new(Token::MathMultiply, Pos::new(1, 25), Span::new(24, 25)),
+            PosToken::new(
+                Token::NumberDecimal("3".to_string(), None),
+                Pos::new(1, 27),
+                Span::new(26, 27)
+            ),
+            PosToken::new(Token::MathDivide, Pos::new(1, 29), Span::new(28, 29)),
+            PosToken::new(
+                Token::NumberDecimal("7".to_string(), None),
+                Pos::new(1, 30),
+                Span::new(29, 30)
+            ),
+            PosToken::new(Token::MathAdd, Pos::new(1, 32), Span::new(31, 32)),
+            PosToken::new(
+                Token::NumberDecimal("1".to_string(), None),
+                Pos::new(1, 33),
+                Span::new(32, 33)
+            ),
         ],
         lex
     );
 
+    let mut interpreter = Interpreter::new(parse(lex).unwrap());
+    assert_eq!(interpreter.run().unwrap(), None);
+    assert_eq!(
+        interpreter.env.get(&"item".to_string()),
+        Some(&Typing::Int64(97))
+    );
+
     // let ast = parse(lex).unwrap();
     // if let PosAst {
     //     ast: Ast::Root(a),
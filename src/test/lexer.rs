@@ -1,15 +1,15 @@
 use crate::{
+    data::{PosToken, Token},
     lexer::{lex, LexerError},
-    Pos,
+    test::lex_err,
+    Pos, Span,
 };
 
 #[test]
 fn test_dec_multi_period() {
-    let lexer = lex(r"12.334.1");
-
     assert_eq!(
-        lexer.err().unwrap().downcast_ref::<LexerError>().unwrap(),
-        &LexerError::DecimalMoreThanOnePeriod {
+        lex_err(r"12.334.1"),
+        LexerError::DecimalMoreThanOnePeriod {
             pos: Pos::new(1, 7)
         }
     );
@@ -21,3 +21,174 @@ fn test_multi() {
     lex("1 != 11").unwrap();
     lex("1!=1").unwrap();
 }
+
+#[test]
+fn test_comments() {
+    let tokens = lex("1 // one\n+ /* two /* nested */ */ 2").unwrap();
+    let tokens: Vec<Token> = tokens.into_iter().map(|t| t.token).collect();
+
+    assert_eq!(
+        tokens,
+        [
+            Token::NumberDecimal("1".to_string(), None),
+            Token::MathAdd,
+            Token::NumberDecimal("2".to_string(), None),
+            Token::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_doc_comments() {
+    let tokens = lex("//! Script docs\n/// Item docs\n//// Not docs\n1").unwrap();
+
+    assert_eq!(
+        tokens[0],
+        PosToken::new(
+            Token::InnerDocComment("Script docs".to_string()),
+            Pos::new(1, 1),
+            Span::new(0, 15)
+        )
+    );
+    assert_eq!(
+        tokens[1],
+        PosToken::new(
+            Token::DocComment("Item docs".to_string()),
+            Pos::new(2, 1),
+            Span::new(16, 29)
+        )
+    );
+    assert_eq!(
+        tokens[2],
+        PosToken::new(
+            Token::NumberDecimal("1".to_string(), None),
+            Pos::new(4, 1),
+            Span::new(44, 45)
+        )
+    );
+}
+
+#[test]
+fn test_block_comment_unterminated() {
+    assert_eq!(
+        lex_err("1 /* a /* b */\n2"),
+        LexerError::BlockCommentUnterminated {
+            pos: Pos::new(1, 3)
+        }
+    );
+}
+
+#[test]
+fn test_unexpected_char() {
+    for (contents, c, col) in [("1 $ 2", '$', 3), ("x@", '@', 2), ("a ?? b", '?', 3)] {
+        assert_eq!(
+            lex_err(contents),
+            LexerError::UnexpectedChar {
+                c,
+                pos: Pos::new(1, col)
+            }
+        );
+    }
+}
+
+#[test]
+fn test_chars() {
+    let tokens = lex(r"'a' '\n' '\t' '\\' '\'' '\0' '\u{1F600}' 'é'").unwrap();
+    let tokens: Vec<Token> = tokens.into_iter().map(|t| t.token).collect();
+
+    assert_eq!(
+        tokens,
+        [
+            Token::Character('a'),
+            Token::Character('\n'),
+            Token::Character('\t'),
+            Token::Character('\\'),
+            Token::Character('\''),
+            Token::Character('\0'),
+            Token::Character('😀'),
+            Token::Character('é'),
+            Token::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_char_errors() {
+    assert_eq!(
+        lex_err("''"),
+        LexerError::CharEmpty {
+            pos: Pos::new(1, 1)
+        }
+    );
+    assert_eq!(
+        lex_err("1 + 'ab'"),
+        LexerError::CharMultiple {
+            pos: Pos::new(1, 5)
+        }
+    );
+    assert_eq!(
+        lex_err("'a\n'"),
+        LexerError::CharUnterminated {
+            pos: Pos::new(1, 1)
+        }
+    );
+    assert_eq!(
+        lex_err("'a"),
+        LexerError::CharUnterminated {
+            pos: Pos::new(1, 1)
+        }
+    );
+    assert_eq!(
+        lex_err(r"'\q'"),
+        LexerError::EscapeBad {
+            c: 'q',
+            pos: Pos::new(1, 2)
+        }
+    );
+    assert_eq!(
+        lex_err(r"'\u{110000}'"),
+        LexerError::EscapeBadUnicode {
+            pos: Pos::new(1, 2)
+        }
+    );
+    assert_eq!(
+        lex_err(r"'\u1F600'"),
+        LexerError::EscapeBadUnicode {
+            pos: Pos::new(1, 2)
+        }
+    );
+    assert_eq!(
+        lex_err(r"'\u{1234567}'"),
+        LexerError::EscapeBadUnicode {
+            pos: Pos::new(1, 2)
+        }
+    );
+}
+
+#[test]
+fn test_spans() {
+    let source = "'é' + \"日本 {x}\"\n  r#\"a\"# über 0xFFu8 1.5e3";
+    let tokens = lex(source).unwrap();
+
+    let text: Vec<&str> = tokens
+        .iter()
+        .map(|t| &source[t.span.start..t.span.end])
+        .collect();
+    assert_eq!(
+        text,
+        [
+            "'é'",
+            "+",
+            "\"日本 {x}\"",
+            "r#\"a\"#",
+            "über",
+            "0xFFu8",
+            "1.5e3",
+            ""
+        ]
+    );
+    assert_eq!(tokens[2].span, Span::new(7, 19));
+    assert_eq!(tokens[4].pos, Pos::new(2, 10));
+    assert_eq!(tokens[4].span, Span::new(29, 34));
+    assert_eq!(tokens[7].span, Span::new(source.len(), source.len()));
+}
@@ -12,7 +12,46 @@ mod macros {
     }
 }
 
+use crate::{
+    data::Typing,
+    lexer::{lex, LexerError},
+    parser::parse,
+    Interpreter, ParseError, RuntimeError,
+};
+
+fn run(contents: &str) -> Option<Typing> {
+    let ast = parse(lex(contents).unwrap()).unwrap();
+    Interpreter::new(ast).run().unwrap()
+}
+
+fn run_err(contents: &str) -> RuntimeError {
+    let ast = parse(lex(contents).unwrap()).unwrap();
+    Interpreter::new(ast).run().err().unwrap()
+}
+
+fn lex_err(contents: &str) -> LexerError {
+    lex(contents).unwrap_err().downcast().unwrap()
+}
+
+fn parse_err(contents: &str) -> ParseError {
+    parse(lex(contents).unwrap())
+        .unwrap_err()
+        .downcast()
+        .unwrap()
+}
+
+mod binary_ops;
+mod blocks;
+mod control_flow;
+mod diagnostics;
+mod functions;
+mod interpreter;
 mod iter_1;
 mod iter_2;
 mod lexer;
+mod number;
 mod parser;
+mod ranges;
+mod source;
+mod string;
+mod variables;
@@ -0,0 +1,249 @@
+use crate::{
+    data::{RawTyping, Token, Typing},
+    lexer::{lex, LexerError},
+    test::{lex_err, parse_err, run},
+    ParseError, Pos, Span,
+};
+
+#[test]
+fn test_radix_lex() {
+    let tokens = lex("0xFF_ff 0o17 0b1010_1010").unwrap();
+    let tokens: Vec<Token> = tokens.into_iter().map(|t| t.token).collect();
+
+    assert_eq!(
+        tokens,
+        [
+            Token::NumberHex("FFff".to_string(), None),
+            Token::NumberOctal("17".to_string(), None),
+            Token::NumberBinary("10101010".to_string(), None),
+            Token::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_radix_values() {
+    assert_eq!(run("0xff"), Some(Typing::Int64(255)));
+    assert_eq!(run("0o777"), Some(Typing::Int64(511)));
+    assert_eq!(run("0b1_0000"), Some(Typing::Int64(16)));
+    assert_eq!(run("0x7FFF_FFFF_FFFF_FFFF"), Some(Typing::Int64(i64::MAX)));
+    assert_eq!(run("0xFFFF_FFFF_FFFF_FFFF"), Some(Typing::UInt64(u64::MAX)));
+    assert_eq!(run("18446744073709551615"), Some(Typing::UInt64(u64::MAX)));
+    assert_eq!(run("0x10 + 0b10"), Some(Typing::Int64(18)));
+}
+
+#[test]
+fn test_radix_errors() {
+    assert_eq!(
+        lex_err("0b102"),
+        LexerError::NumberBadDigit {
+            c: '2',
+            radix: 2,
+            pos: Pos::new(1, 5)
+        }
+    );
+    assert_eq!(
+        lex_err("1 + 0o8"),
+        LexerError::NumberBadDigit {
+            c: '8',
+            radix: 8,
+            pos: Pos::new(1, 7)
+        }
+    );
+    assert_eq!(
+        lex_err("0xG"),
+        LexerError::NumberBadDigit {
+            c: 'G',
+            radix: 16,
+            pos: Pos::new(1, 3)
+        }
+    );
+    assert_eq!(
+        lex_err("0x + 1"),
+        LexerError::NumberMissingDigits {
+            radix: 16,
+            pos: Pos::new(1, 1)
+        }
+    );
+    assert_eq!(
+        lex_err("0x1_0000_0000_0000_0000"),
+        LexerError::NumberOutOfRange {
+            radix: 16,
+            pos: Pos::new(1, 1)
+        }
+    );
+}
+
+#[test]
+fn test_float_lex() {
+    let tokens = lex("1.5 1e-9 2.5E3 7e+2").unwrap();
+    let tokens: Vec<Token> = tokens.into_iter().map(|t| t.token).collect();
+
+    assert_eq!(
+        tokens,
+        [
+            Token::NumberDecimal("1.5".to_string(), None),
+            Token::NumberDecimal("1e-9".to_string(), None),
+            Token::NumberDecimal("2.5E3".to_string(), None),
+            Token::NumberDecimal("7e+2".to_string(), None),
+            Token::Eof,
+        ]
+    );
+
+    assert_eq!(
+        lex_err("1e5.3"),
+        LexerError::DecimalBadToken {
+            c: '.',
+            pos: Pos::new(1, 4)
+        }
+    );
+    assert_eq!(
+        lex_err("1e5e3"),
+        LexerError::DecimalBadToken {
+            c: 'e',
+            pos: Pos::new(1, 4)
+        }
+    );
+}
+
+#[test]
+fn test_float_values() {
+    assert_eq!(run("1.5"), Some(Typing::Float64(1.5)));
+    assert_eq!(run("1e-9"), Some(Typing::Float64(1e-9)));
+    assert_eq!(run("2.5E3"), Some(Typing::Float64(2500.0)));
+    assert_eq!(run("-0.25"), Some(Typing::Float64(-0.25)));
+    assert_eq!(run("1.5 * 4.0"), Some(Typing::Float64(6.0)));
+    assert_eq!(run("7.5 % 2.0"), Some(Typing::Float64(1.5)));
+    assert_eq!(run("1.0 <= 1.0"), Some(Typing::Boolean(true)));
+    assert_eq!(run("2.5 > 3.0"), Some(Typing::Boolean(false)));
+}
+
+#[test]
+fn test_float_mixed() {
+    assert_eq!(run("1 + 0.5"), Some(Typing::Float64(1.5)));
+    assert_eq!(run("0.5 * 3"), Some(Typing::Float64(1.5)));
+    assert_eq!(run("3 / 2.0"), Some(Typing::Float64(1.5)));
+    assert_eq!(run("2 == 2.0"), Some(Typing::Boolean(true)));
+    assert_eq!(run("1 < 1.5"), Some(Typing::Boolean(true)));
+}
+
+#[test]
+fn test_float_nan_inf() {
+    assert_eq!(run("1.0 / 0.0"), Some(Typing::Float64(f64::INFINITY)));
+    assert_eq!(run("-1.0 / 0.0"), Some(Typing::Float64(f64::NEG_INFINITY)));
+    assert_eq!(run("1e308 * 10.0"), Some(Typing::Float64(f64::INFINITY)));
+    assert!(matches!(run("0.0 / 0.0"), Some(Typing::Float64(f)) if f.is_nan()));
+    assert!(matches!(run("1.0 % 0.0"), Some(Typing::Float64(f)) if f.is_nan()));
+
+    // NaN is not equal to anything, including itself.
+    assert_eq!(
+        run("(0.0 / 0.0) == (0.0 / 0.0)"),
+        Some(Typing::Boolean(false))
+    );
+    assert_eq!(
+        run("(0.0 / 0.0) != (0.0 / 0.0)"),
+        Some(Typing::Boolean(true))
+    );
+    assert_eq!(run("(0.0 / 0.0) < 1.0"), Some(Typing::Boolean(false)));
+    assert_eq!(run("(0.0 / 0.0) >= 1.0"), Some(Typing::Boolean(false)));
+}
+
+#[test]
+fn test_suffix_lex() {
+    let tokens = lex("255u8 10u64 0xFFu8 3f64 1_000i64").unwrap();
+    let tokens: Vec<Token> = tokens.into_iter().map(|t| t.token).collect();
+
+    assert_eq!(
+        tokens,
+        [
+            Token::NumberDecimal("255".to_string(), Some(RawTyping::UInt8)),
+            Token::NumberDecimal("10".to_string(), Some(RawTyping::UInt64)),
+            Token::NumberHex("FF".to_string(), Some(RawTyping::UInt8)),
+            Token::NumberDecimal("3".to_string(), Some(RawTyping::Float64)),
+            Token::NumberDecimal("1000".to_string(), Some(RawTyping::Int64)),
+            Token::Eof,
+        ]
+    );
+
+    assert_eq!(
+        lex_err("1 + 10u32"),
+        LexerError::NumberBadSuffix {
+            suffix: "u32".to_string(),
+            pos: Pos::new(1, 7)
+        }
+    );
+    assert_eq!(
+        lex_err("12abc"),
+        LexerError::NumberBadSuffix {
+            suffix: "abc".to_string(),
+            pos: Pos::new(1, 3)
+        }
+    );
+}
+
+#[test]
+fn test_suffix_values() {
+    assert_eq!(run("255u8"), Some(Typing::UInt8(255)));
+    assert_eq!(run("0b1111_0000u8"), Some(Typing::UInt8(0xF0)));
+    assert_eq!(run("10u64"), Some(Typing::UInt64(10)));
+    assert_eq!(
+        run("0xFFFF_FFFF_FFFF_FFFFu64"),
+        Some(Typing::UInt64(u64::MAX))
+    );
+    assert_eq!(run("7i64"), Some(Typing::Int64(7)));
+    assert_eq!(run("3f64"), Some(Typing::Float64(3.0)));
+    assert_eq!(run("2.5e1f64"), Some(Typing::Float64(25.0)));
+    assert_eq!(
+        run("18_446_744_073_709_551_616f64"),
+        Some(Typing::Float64(18446744073709551616.0))
+    );
+}
+
+#[test]
+fn test_suffix_range() {
+    assert_eq!(
+        parse_err("256u8"),
+        ParseError::NumberOutOfRange {
+            num: "256".to_string(),
+            ty: RawTyping::UInt8,
+            pos: Pos::new(1, 1),
+            span: Span::new(0, 5),
+        }
+    );
+    assert_eq!(
+        parse_err("1 + 18_446_744_073_709_551_616"),
+        ParseError::NumberOutOfRange {
+            num: "18446744073709551616".to_string(),
+            ty: RawTyping::UInt64,
+            pos: Pos::new(1, 5),
+            span: Span::new(4, 30),
+        }
+    );
+    assert_eq!(
+        parse_err("1 + 0x8000_0000_0000_0000i64"),
+        ParseError::NumberOutOfRange {
+            num: "8000000000000000".to_string(),
+            ty: RawTyping::Int64,
+            pos: Pos::new(1, 5),
+            span: Span::new(4, 28),
+        }
+    );
+    assert_eq!(
+        parse_err("1.5u8"),
+        ParseError::NumberFloatSuffix {
+            num: "1.5".to_string(),
+            suffix: RawTyping::UInt8,
+            pos: Pos::new(1, 1),
+            span: Span::new(0, 5),
+        }
+    );
+    assert_eq!(
+        parse_err("1e400"),
+        ParseError::NumberOutOfRange {
+            num: "1e400".to_string(),
+            ty: RawTyping::Float64,
+            pos: Pos::new(1, 1),
+            span: Span::new(0, 5),
+        }
+    );
+}
@@ -1,4 +1,10 @@
-use crate::{lexer::lex, parser::parse};
+use crate::{
+    data::{Ast, PosAst, Token, Typing},
+    lexer::lex,
+    parser::{parse, MAX_NESTING_DEPTH},
+    test::{parse_err, run},
+    Interpreter, ParseError, Pos, Span,
+};
 
 #[test]
 fn test_parser() {
@@ -16,3 +22,288 @@ fn test_parser() {
         parse(lex(r"1 | 10  & 12 | ( 1 & 1)").unwrap()).unwrap()
     );
 }
+
+#[test]
+fn test_char_value() {
+    let ast = parse(lex(r"'\u{41}'").unwrap()).unwrap();
+    assert_eq!(
+        Interpreter::new(ast).run().unwrap(),
+        Some(Typing::Character('A'))
+    );
+}
+
+#[test]
+fn test_parser_errors() {
+    assert_eq!(
+        parse_err("(1 + 2"),
+        ParseError::ExpectedToken {
+            expected: Token::RParentheses,
+            found: Token::Eof,
+            pos: Pos::new(1, 7),
+            span: Span::new(6, 6),
+        }
+    );
+    assert_eq!(
+        parse_err("1 +"),
+        ParseError::ExpectedExpression {
+            found: Token::Eof,
+            pos: Pos::new(1, 4),
+            span: Span::new(3, 3),
+        }
+    );
+    assert_eq!(
+        parse_err("1 + )"),
+        ParseError::ExpectedExpression {
+            found: Token::RParentheses,
+            pos: Pos::new(1, 5),
+            span: Span::new(4, 5),
+        }
+    );
+    assert_eq!(
+        parse_err("1 2"),
+        ParseError::ExpectedToken {
+            expected: Token::SemiColon,
+            found: Token::NumberDecimal("2".to_string(), None),
+            pos: Pos::new(1, 3),
+            span: Span::new(2, 3),
+        }
+    );
+    assert_eq!(
+        parse_err("(1 + 2").to_string(),
+        "Parser: Expected ')' but found end of file at (1:7)."
+    );
+}
+
+#[test]
+fn test_nesting_depth() {
+    let parens = |depth| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+    let chain = |terms| format!("1{}", " + 1".repeat(terms - 1));
+
+    assert_eq!(run(&parens(MAX_NESTING_DEPTH - 1)), Some(Typing::Int64(1)));
+    assert_eq!(
+        parse_err(&parens(MAX_NESTING_DEPTH)),
+        ParseError::NestingDepth {
+            pos: Pos::new(1, MAX_NESTING_DEPTH + 1),
+            span: Span::new(256, 257),
+        }
+    );
+    // The statement is one level, every + is one more.
+    assert_eq!(
+        run(&chain(MAX_NESTING_DEPTH)),
+        Some(Typing::Int64(MAX_NESTING_DEPTH as i64))
+    );
+    assert_eq!(
+        parse_err(&chain(MAX_NESTING_DEPTH + 1)),
+        ParseError::NestingDepth {
+            pos: Pos::new(1, 4 * MAX_NESTING_DEPTH - 1),
+            span: Span::new(1022, 1023),
+        }
+    );
+    assert!(matches!(
+        parse_err(&format!("{}1", "-".repeat(MAX_NESTING_DEPTH))),
+        ParseError::NestingDepth { .. }
+    ));
+    // An operator is one level above the deeper of its sides.
+    let half = MAX_NESTING_DEPTH / 2 + 10;
+    assert_eq!(
+        run(&format!("{} + {}", parens(half), parens(half))),
+        Some(Typing::Int64(2))
+    );
+    assert!(matches!(
+        parse_err(&format!("({}) + {}", chain(half), chain(half))),
+        ParseError::NestingDepth { .. }
+    ));
+}
+
+/// Renders the expression as an s-expression, like (+ 1 (* 2 3)).
+fn tree(contents: &str) -> String {
+    fn render(ast: &PosAst) -> String {
+        match &ast.ast {
+            Ast::Root(stmts) | Ast::Block(stmts) => {
+                let stmts: Vec<_> = stmts.iter().map(render).collect();
+                match &ast.ast {
+                    Ast::Block(_) => format!("{{{}}}", stmts.join(" ")),
+                    _ => stmts.join(" "),
+                }
+            }
+            Ast::Statement(a) => format!("{};", render(a)),
+            Ast::Declaration(kind, id, _, a) => format!("({kind} {id} {})", render(a)),
+            Ast::Assignment(id, a) => format!("(= {id} {})", render(a)),
+            Ast::UnaryOp(op, a) => format!("({op} {})", render(a)),
+            Ast::BinOp(op, a1, a2) => format!("({op} {} {})", render(a1), render(a2)),
+            Ast::Value(val) => val.to_string(),
+            ast => panic!("Can not render {ast:?}"),
+        }
+    }
+
+    render(&parse(lex(contents).unwrap()).unwrap())
+}
+
+#[test]
+fn test_precedence_left_to_right() {
+    assert_eq!(tree("8 * 4 / 2 % 3"), "(% (/ (* 8 4) 2) 3)");
+    assert_eq!(tree("10 - 5 - 2"), "(- (- 10 5) 2)");
+    assert_eq!(tree("10 - 5 + 2"), "(+ (- 10 5) 2)");
+    assert_eq!(tree("1 << 2 >> 3"), "(>> (<< 1 2) 3)");
+    assert_eq!(tree("1 & 2 & 3"), "(& (& 1 2) 3)");
+    assert_eq!(tree("1 ^ 2 ^ 3"), "(^ (^ 1 2) 3)");
+    assert_eq!(tree("1 | 2 | 3"), "(| (| 1 2) 3)");
+    assert_eq!(tree("1 && 2 && 3"), "(&& (&& 1 2) 3)");
+    assert_eq!(tree("1 ^^ 2 ^^ 3"), "(^^ (^^ 1 2) 3)");
+    assert_eq!(tree("1 || 2 || 3"), "(|| (|| 1 2) 3)");
+}
+
+#[test]
+fn test_precedence_rows() {
+    // Unary
+    assert_eq!(tree("-1 * 2"), "(* (- 1) 2)");
+    assert_eq!(tree("- - 1"), "(- (- 1))");
+    // * / %
+    assert_eq!(tree("1 + 2 * 3"), "(+ 1 (* 2 3))");
+    assert_eq!(tree("1 * 2 - 3 % 4"), "(- (* 1 2) (% 3 4))");
+    // + -
+    assert_eq!(tree("1 << 2 + 3"), "(<< 1 (+ 2 3))");
+    // << >>
+    assert_eq!(tree("1 & 2 >> 3"), "(& 1 (>> 2 3))");
+    // &
+    assert_eq!(tree("1 ^ 2 & 3"), "(^ 1 (& 2 3))");
+    // ^
+    assert_eq!(tree("1 | 2 ^ 3"), "(| 1 (^ 2 3))");
+    // |
+    assert_eq!(tree("1 == 2 | 3"), "(== 1 (| 2 3))");
+    assert_eq!(tree("1 | 2 < 3"), "(< (| 1 2) 3)");
+    // == != < > <= >=
+    assert_eq!(tree("1 != 2 && 3 >= 4"), "(&& (!= 1 2) (>= 3 4))");
+    assert_eq!(tree("1 <= 2 ^^ 3 > 4"), "(^^ (<= 1 2) (> 3 4))");
+    // &&
+    assert_eq!(tree("1 ^^ 2 && 3"), "(^^ 1 (&& 2 3))");
+    // ^^
+    assert_eq!(tree("1 || 2 ^^ 3"), "(|| 1 (^^ 2 3))");
+    // ||
+    assert_eq!(tree("1 && 2 || 3 && 4"), "(|| (&& 1 2) (&& 3 4))");
+    // Parentheses
+    assert_eq!(tree("(1 + 2) * 3"), "(* (+ 1 2) 3)");
+    assert_eq!(tree("10 - (5 - 2)"), "(- 10 (- 5 2))");
+}
+
+#[test]
+fn test_precedence_compare_require_parentheses() {
+    assert_eq!(
+        parse_err("1 < 2 < 3"),
+        ParseError::RequireParentheses {
+            first: Token::CompareLess,
+            first_pos: Pos::new(1, 3),
+            first_span: Span::new(2, 3),
+            found: Token::CompareLess,
+            pos: Pos::new(1, 7),
+            span: Span::new(6, 7),
+            suggestion: "(1 < 2) < 3".to_string(),
+        }
+    );
+    assert_eq!(
+        parse_err("1 == 2 != 3 + 4"),
+        ParseError::RequireParentheses {
+            first: Token::CompareEqual,
+            first_pos: Pos::new(1, 3),
+            first_span: Span::new(2, 4),
+            found: Token::CompareNotEqual,
+            pos: Pos::new(1, 8),
+            span: Span::new(7, 9),
+            suggestion: "(1 == 2) != 3 + 4".to_string(),
+        }
+    );
+    assert_eq!(tree("(1 < 2) == (3 < 4)"), "(== (< 1 2) (< 3 4))");
+}
+
+#[test]
+fn test_require_parentheses_suggestion() {
+    let suggestion = |contents| match parse_err(contents) {
+        ParseError::RequireParentheses { suggestion, .. } => suggestion,
+        err => panic!("Expected RequireParentheses, got {err:?}"),
+    };
+
+    assert_eq!(
+        suggestion("1 + 2 * 3 <= 4 > -5 | 6"),
+        "(1 + 2 * 3 <= 4) > -5 | 6"
+    );
+    assert_eq!(
+        suggestion("(1 - 2) - 3 < 0.5 == 'a'"),
+        "(1 - 2 - 3 < 0.5) == 'a'"
+    );
+    assert_eq!(
+        suggestion("1 - (2 - 3) >= 255u8 != -(1 + 1)"),
+        "(1 - (2 - 3) >= 255u8) != -(1 + 1)"
+    );
+    assert_eq!(suggestion("1 == 1 && 1 < 2 < 3 && 0"), "(1 < 2) < 3");
+    assert_eq!(suggestion("1 < 2 < )"), "(1 < 2) < ...");
+
+    assert_eq!(
+        parse_err("1 < 2 < 3").to_string(),
+        "Parser: '<' at (1:7) can not be chained with '<' at (1:3), add parentheses like `(1 < 2) < 3`."
+    );
+}
+
+#[test]
+fn test_left_to_right_values() {
+    assert_eq!(run("10 - 5 - 2"), Some(Typing::Int64(3)));
+    assert_eq!(run("100 / 10 / 5"), Some(Typing::Int64(2)));
+    assert_eq!(run("2 * 3 + 4 * 5"), Some(Typing::Int64(26)));
+}
+
+#[test]
+fn test_spans() {
+    fn walk<'a>(ast: &PosAst, source: &'a str, out: &mut Vec<&'a str>) {
+        out.push(&source[ast.span.start..ast.span.end]);
+        match &ast.ast {
+            Ast::Root(stmts) | Ast::Block(stmts) => {
+                stmts.iter().for_each(|a| walk(a, source, out));
+            }
+            Ast::Statement(a) | Ast::UnaryOp(_, a) => walk(a, source, out),
+            Ast::BinOp(_, a1, a2) => {
+                walk(a1, source, out);
+                walk(a2, source, out);
+            }
+            _ => {}
+        }
+    }
+
+    let source = " -( 1 + 2 ) * 'é'\n";
+    let ast = parse(lex(source).unwrap()).unwrap();
+    let mut text = Vec::new();
+    walk(&ast, source, &mut text);
+
+    assert_eq!(ast.span, Span::new(0, source.len()));
+    assert_eq!(
+        text,
+        [
+            source,
+            "-( 1 + 2 ) * 'é'",
+            "-( 1 + 2 )",
+            "( 1 + 2 )",
+            "1",
+            "2",
+            "'é'"
+        ]
+    );
+}
+
+#[test]
+fn test_statement_tree() {
+    assert_eq!(tree("1; 2"), "1; 2");
+    assert_eq!(tree("let x = { 1; 2 }; x"), "(let x {1; 2}); x");
+    assert_eq!(tree("{ 1 } { 2 } 3"), "{1}; {2}; 3");
+    assert_eq!(tree("{ 1 } - 2"), "(- {1} 2)");
+
+    let source = "{ 1 };\nlet x = 2 ;";
+    let ast = parse(lex(source).unwrap()).unwrap();
+    let Ast::Root(stmts) = &ast.ast
+    else {
+        panic!()
+    };
+    let spans: Vec<_> = stmts
+        .iter()
+        .map(|a| &source[a.span.start..a.span.end])
+        .collect();
+    assert_eq!(spans, ["{ 1 };", "let x = 2 ;"]);
+    assert_eq!(stmts[1].pos, Pos::new(2, 5));
+}
@@ -0,0 +1,161 @@
+use crate::{
+    data::{RawTyping, Token, Typing},
+    test::{parse_err, run, run_err},
+    ParseError, Pos, RuntimeError, Span,
+};
+
+/// Values of a for loop over range, joined with spaces.
+fn values(range: &str) -> String {
+    let contents = format!("var s = \"\"; for i in {range} {{ s = \"{{s}}{{i}} \" }} s");
+    match run(&contents) {
+        Some(Typing::String(s)) => s.string().trim_end().to_string(),
+        val => panic!("Expected a string, got {val:?}"),
+    }
+}
+
+#[test]
+fn test_for() {
+    assert_eq!(values("0..<5"), "0 1 2 3 4");
+    assert_eq!(values("0..=5"), "0 1 2 3 4 5");
+    assert_eq!(values("3..<3"), "");
+    assert_eq!(values("5..<0"), "");
+    assert_eq!(values("0..<10 by 3"), "0 3 6 9");
+    assert_eq!(values("0..=9 by 3"), "0 3 6 9");
+    assert_eq!(values("5..=1 by -1"), "5 4 3 2 1");
+    assert_eq!(values("5..<1 by -2"), "5 3");
+    assert_eq!(values("-2..<2"), "-2 -1 0 1");
+    assert_eq!(values("1 + 1..<2 * 3"), "2 3 4 5");
+
+    // Every integer type, the step is always an i64.
+    assert_eq!(values("250u8..=255u8"), "250 251 252 253 254 255");
+    assert_eq!(values("255u8..=0u8 by -85"), "255 170 85 0");
+    assert_eq!(
+        values("18446744073709551613u64..=18446744073709551615u64"),
+        "18446744073709551613 18446744073709551614 18446744073709551615"
+    );
+    assert_eq!(
+        values("9223372036854775806..=9223372036854775807"),
+        "9223372036854775806 9223372036854775807"
+    );
+    assert_eq!(run("for i in 0u8..<3u8 { ret i }"), Some(Typing::UInt8(0)));
+
+    assert_eq!(
+        run("var sum = 0; for i in 0..<10 { if i % 2 == 0 { continue } if i > 7 { break } sum += i } sum"),
+        Some(Typing::Int64(16))
+    );
+    assert_eq!(
+        run("let found = for i in 1..<100 { if i * i > 50 { break i } }; found"),
+        Some(Typing::Int64(8))
+    );
+    assert_eq!(run("for i in 0..<3 { i }"), None);
+    assert_eq!(
+        run("var n = 0; 'rows: for y in 0..<3 { for x in 0..<3 { if x > y { continue 'rows } n += 1 } } n"),
+        Some(Typing::Int64(6))
+    );
+
+    // The loop variable is immutable and only in scope of its iteration.
+    assert!(matches!(
+        run_err("for i in 0..<3 { i = 1 }"),
+        RuntimeError::AssignImmutable { .. }
+    ));
+    assert!(matches!(
+        run_err("for i in 0..<3 { } i"),
+        RuntimeError::UndefinedVariable { .. }
+    ));
+    assert_eq!(
+        run("let i = 10; var sum = 0; for i in 0..<3 { sum += i } sum + i"),
+        Some(Typing::Int64(13))
+    );
+}
+
+#[test]
+fn test_range_values() {
+    assert_eq!(
+        run("let r = 0..<3; var sum = 0; for i in r { sum += i } for i in r { sum += i } sum"),
+        Some(Typing::Int64(6))
+    );
+    assert_eq!(
+        run("let r: range = 1..=3; \"{r}\""),
+        Some(Typing::String(Box::new("1..=3".into())))
+    );
+    assert_eq!(
+        run("let r = 10..<0 by -2; \"{r}\""),
+        Some(Typing::String(Box::new("10..<0 by -2".into())))
+    );
+    assert_eq!(
+        run("const R = 0..<10 by 2; var n = 0; for i in R { n += 1 } n"),
+        Some(Typing::Int64(5))
+    );
+    assert_eq!(
+        parse_err("let n = 3; const R = 0..<n"),
+        ParseError::ConstNotConstant {
+            id: "R".to_string(),
+            pos: Pos::new(1, 26),
+            span: Span::new(25, 26),
+        }
+    );
+}
+
+#[test]
+fn test_range_errors() {
+    assert_eq!(
+        run_err("for i in 0..<3u8 { }"),
+        RuntimeError::RangeBounds {
+            start: RawTyping::Int64,
+            end: RawTyping::UInt8,
+            pos: Pos::new(1, 11),
+            span: Span::new(9, 16),
+        }
+    );
+    assert_eq!(
+        run_err("0.5..<3.5").to_string(),
+        "Runtime: A range must start and end with integers of the same type, but has f64 and f64 at (1:4)."
+    );
+    assert_eq!(
+        run_err("0..<3 by 0"),
+        RuntimeError::RangeStepZero {
+            pos: Pos::new(1, 10),
+            span: Span::new(9, 10),
+        }
+    );
+    assert_eq!(
+        run_err("0u8..<3u8 by 1u8"),
+        RuntimeError::RangeStepType {
+            found: RawTyping::UInt8,
+            pos: Pos::new(1, 14),
+            span: Span::new(13, 16),
+        }
+    );
+    assert_eq!(
+        run_err("for i in 3 { }"),
+        RuntimeError::NotIterable {
+            found: RawTyping::Int64,
+            pos: Pos::new(1, 10),
+            span: Span::new(9, 10),
+        }
+    );
+
+    assert_eq!(
+        parse_err("0..<5..<10"),
+        ParseError::RequireParentheses {
+            first: Token::RangeExclusive,
+            first_pos: Pos::new(1, 2),
+            first_span: Span::new(1, 4),
+            found: Token::RangeExclusive,
+            pos: Pos::new(1, 6),
+            span: Span::new(5, 8),
+            suggestion: "(0 ..< 5) ..< 10".to_string(),
+        }
+    );
+    assert!(matches!(
+        parse_err("for 1 in 0..<3 { }"),
+        ParseError::ExpectedIdent { .. }
+    ));
+    assert!(matches!(
+        parse_err("for i 0..<3 { }"),
+        ParseError::ExpectedToken {
+            expected: Token::In,
+            ..
+        }
+    ));
+}
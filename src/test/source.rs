@@ -0,0 +1,76 @@
+use crate::{
+    diagnostics::render_file, lexer::lex, parser::parse, LexerError, ParseError, Pos, SourceFile,
+    Span,
+};
+
+#[test]
+fn test_pos() {
+    assert_eq!(Pos::new(2, 7).line(), 2);
+    assert_eq!(Pos::new(2, 7).col(), 7);
+    assert!(Pos::new(1, 9) < Pos::new(2, 1));
+    assert!(Pos::new(2, 1) < Pos::new(2, 3));
+    assert_eq!(Pos::new(3, 1).max(Pos::new(2, 9)), Pos::new(3, 1));
+}
+
+#[test]
+fn test_pos_to_offset() {
+    let source = "ab\nçd\n\nx";
+    assert_eq!(Pos::new(1, 1).to_offset(source), Some(0));
+    assert_eq!(Pos::new(1, 3).to_offset(source), Some(2));
+    assert_eq!(Pos::new(2, 1).to_offset(source), Some(3));
+    assert_eq!(Pos::new(2, 2).to_offset(source), Some(5));
+    assert_eq!(Pos::new(3, 1).to_offset(source), Some(7));
+    assert_eq!(Pos::new(4, 2).to_offset(source), Some(9));
+    assert_eq!(Pos::new(1, 4).to_offset(source), None);
+    assert_eq!(Pos::new(5, 1).to_offset(source), None);
+    assert_eq!(Pos::new(0, 1).to_offset(source), None);
+    assert_eq!(Pos::new(1, 0).to_offset(source), None);
+}
+
+#[test]
+fn test_source_file() {
+    let file = SourceFile::new("main.kot", "ab\nçd\n\nx");
+    assert_eq!(file.name(), "main.kot");
+    assert_eq!(file.line_count(), 4);
+    assert_eq!(file.line(2), Some("çd"));
+    assert_eq!(file.line(3), Some(""));
+    assert_eq!(file.line(4), Some("x"));
+    assert_eq!(file.line(5), None);
+    assert_eq!(file.line(0), None);
+
+    for offset in [0, 2, 3, 5, 7, 8, 9] {
+        let pos = file.pos(offset).unwrap();
+        assert_eq!(file.offset(pos), Some(offset));
+        assert_eq!(pos.to_offset(file.text()), Some(offset));
+    }
+    assert_eq!(file.pos(5), Some(Pos::new(2, 2)));
+    assert_eq!(file.pos(4), None);
+    assert_eq!(file.pos(10), None);
+    assert_eq!(file.slice(Span::new(3, 6)), Some("çd"));
+    assert_eq!(file.slice(Span::new(3, 4)), None);
+}
+
+#[test]
+fn test_error_pos() {
+    let file = SourceFile::new("main.kot", "1 +\n 0b2");
+    let err = lex(file.text()).unwrap_err();
+    let pos = err.downcast_ref::<LexerError>().unwrap().pos();
+    assert_eq!(pos, Pos::new(2, 4));
+    assert_eq!(file.offset(pos), Some(7));
+    assert_eq!(
+        render_file(&file, &err, false),
+        "\
+error: Lexer: Invalid digit '2' for a base 2 number at (2:4).
+ --> main.kot:2:4
+  |
+2 |  0b2
+  |    ^ not a base 2 digit
+"
+    );
+
+    let err = parse(lex("(1").unwrap()).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ParseError>().unwrap().pos(),
+        Pos::new(1, 3)
+    );
+}
@@ -0,0 +1,133 @@
+use crate::{
+    data::{ChoppedString, DeclarationKind, Environment, Token, Typing},
+    lexer::{lex, LexerError},
+    parser::parse,
+    test::lex_err,
+    Interpreter, Pos, RuntimeError, Span,
+};
+
+fn lex_string(contents: &str) -> ChoppedString {
+    let mut tokens = lex(contents).unwrap();
+    match tokens.remove(0).token {
+        Token::String(string) => string,
+        token => panic!("Expected string, got {token:?}"),
+    }
+}
+
+fn string(string: &str) -> Typing {
+    Typing::String(Box::new(string.into()))
+}
+
+#[test]
+fn test_string_lex() {
+    assert_eq!(lex_string(r#""hello""#), "hello".into());
+    assert_eq!(
+        lex_string(r#""a\tb\n\"c\" \u{1F600}""#),
+        "a\tb\n\"c\" 😀".into()
+    );
+    assert_eq!(lex_string(r#""\{not\} \{""#).string(), "{not} {");
+    assert_eq!(lex_string("\"two\nlines\""), "two\nlines".into());
+    assert_eq!(
+        lex_string(r##"#"say "hi" {name}"#"##).string(),
+        r#"say "hi" "#
+    );
+}
+
+#[test]
+fn test_raw_string_lex() {
+    assert_eq!(lex_string(r#"r"C:\path\{x}""#), r"C:\path\{x}".into());
+    assert_eq!(
+        lex_string(r###"r#"a "quoted" \n"#"###),
+        r#"a "quoted" \n"#.into()
+    );
+    assert_eq!(lex_string(r###"r##"a "# b"##"###), r##"a "# b"##.into());
+}
+
+#[test]
+fn test_string_interpolation_lex() {
+    let string = lex_string(r#""{a} + {b_2} = {sum}!""#);
+
+    assert_eq!(string.string(), " +  = !");
+    assert_eq!(
+        string.fill(),
+        [
+            ("sum".to_string(), 6),
+            ("b_2".to_string(), 3),
+            ("a".to_string(), 0),
+        ]
+    );
+}
+
+#[test]
+fn test_string_errors() {
+    assert_eq!(
+        lex_err(r#"1 + "abc"#),
+        LexerError::StringUnterminated {
+            pos: Pos::new(1, 5)
+        }
+    );
+    assert_eq!(
+        lex_err(r###"r#"abc""###),
+        LexerError::StringUnterminated {
+            pos: Pos::new(1, 1)
+        }
+    );
+    assert_eq!(
+        lex_err(r#""a {1}""#),
+        LexerError::StringBadInterpolation {
+            pos: Pos::new(1, 4)
+        }
+    );
+    assert_eq!(
+        lex_err(r#""a {b""#),
+        LexerError::StringBadInterpolation {
+            pos: Pos::new(1, 4)
+        }
+    );
+    assert_eq!(
+        lex_err(r#""\x""#),
+        LexerError::EscapeBad {
+            c: 'x',
+            pos: Pos::new(1, 2)
+        }
+    );
+    assert_eq!(
+        lex_err("r#x"),
+        LexerError::StringMissingQuote {
+            pos: Pos::new(1, 3)
+        }
+    );
+}
+
+#[test]
+fn test_string_fill() {
+    let mut env = Environment::new();
+    env.declare("name".to_string(), DeclarationKind::Var, string("kot"));
+    env.declare("n".to_string(), DeclarationKind::Var, Typing::Int64(3));
+    env.declare(
+        "c".to_string(),
+        DeclarationKind::Var,
+        Typing::Character('!'),
+    );
+
+    let ast = parse(lex(r#""{name} has {n} tests{c}""#).unwrap()).unwrap();
+    let result = Interpreter::new_with_environment(ast, env).run().unwrap();
+
+    assert_eq!(result, Some(string("kot has 3 tests!")));
+}
+
+#[test]
+fn test_string_fill_undefined() {
+    let ast = parse(lex(r#"  "hi {name}""#).unwrap()).unwrap();
+    let err = Interpreter::new(ast).run().err().unwrap();
+
+    assert_eq!(
+        err,
+        RuntimeError::UndefinedVariable {
+            id: "name".to_string(),
+            pos: Pos::new(1, 3),
+            span: Span::new(2, 13),
+            suggestions: Vec::new()
+        }
+    );
+}
@@ -0,0 +1,393 @@
+use crate::{
+    data::{DeclarationKind, Environment, RawTyping, SetError, Token, Typing},
+    lexer::lex,
+    parser::parse,
+    test::{parse_err, run, run_err},
+    Interpreter, ParseError, Pos, RuntimeError, Span,
+};
+
+fn run_env(contents: &str) -> Environment {
+    let mut interpreter = Interpreter::new(parse(lex(contents).unwrap()).unwrap());
+    assert_eq!(interpreter.run().unwrap(), None);
+    interpreter.env
+}
+
+/// Runs contents with the variables of env.
+fn run_with(
+    env: Environment,
+    contents: &str,
+) -> Result<(Option<Typing>, Environment), RuntimeError> {
+    let mut interpreter =
+        Interpreter::new_with_environment(parse(lex(contents).unwrap()).unwrap(), env);
+    let val = interpreter.run()?;
+    Ok((val, interpreter.env))
+}
+
+#[test]
+fn test_declarations() {
+    let env = run_env("let x = 1 + 2;");
+    assert_eq!(env.get(&"x".to_string()), Some(&Typing::Int64(3)));
+    assert_eq!(
+        env.get_variable(&"x".to_string()).unwrap().kind,
+        DeclarationKind::Let
+    );
+
+    let env = run_env("var name: string = \"kot\"");
+    assert_eq!(
+        env.get(&"name".to_string()),
+        Some(&Typing::String(Box::new("kot".into())))
+    );
+    assert_eq!(
+        env.get_variable(&"name".to_string()).unwrap().kind,
+        DeclarationKind::Var
+    );
+
+    let env = run_env("const HOUR: int = 60 * 60");
+    assert_eq!(env.get(&"HOUR".to_string()), Some(&Typing::Int64(3600)));
+
+    for (ty, val) in [
+        ("i64", "1"),
+        ("int", "1"),
+        ("u64", "1u64"),
+        ("uint", "1u64"),
+        ("f64", "1.5"),
+        ("float", "1.5"),
+        ("u8", "1u8"),
+        ("byte", "1u8"),
+        ("bool", "true"),
+        ("char", "'c'"),
+        ("string", "\"s\""),
+        ("range", "0..<1"),
+    ] {
+        run_env(&format!("let x: {ty} = {val}"));
+    }
+}
+
+#[test]
+fn test_declaration_errors() {
+    assert_eq!(
+        run_err("let x: u8 = 1"),
+        RuntimeError::VariableTypeMismatch {
+            id: "x".to_string(),
+            expected: RawTyping::UInt8,
+            found: RawTyping::Int64,
+            pos: Pos::new(1, 5),
+            span: Span::new(0, 13),
+        }
+    );
+    assert_eq!(
+        run_err("let x: u8 = 1").to_string(),
+        "Runtime: 'x' has type u8 but was given i64 at (1:5)."
+    );
+
+    assert_eq!(
+        parse_err("let x: number = 1"),
+        ParseError::UnknownType {
+            name: "number".to_string(),
+            pos: Pos::new(1, 8),
+            span: Span::new(7, 13),
+        }
+    );
+    assert!(matches!(
+        parse_err("let = 1"),
+        ParseError::ExpectedIdent { .. }
+    ));
+    assert!(matches!(
+        parse_err("let x"),
+        ParseError::ExpectedToken {
+            expected: Token::Assign,
+            ..
+        }
+    ));
+    assert_eq!(
+        parse_err("const GREETING = \"hi {name}\""),
+        ParseError::ConstNotConstant {
+            id: "GREETING".to_string(),
+            pos: Pos::new(1, 18),
+            span: Span::new(17, 28),
+        }
+    );
+
+    let mut env = Environment::new();
+    env.declare("x".to_string(), DeclarationKind::Let, Typing::Int64(1));
+    let mut interpreter =
+        Interpreter::new_with_environment(parse(lex("let x = 2").unwrap()).unwrap(), env);
+    assert_eq!(
+        interpreter.run(),
+        Err(RuntimeError::Redeclared {
+            id: "x".to_string(),
+            pos: Pos::new(1, 5),
+            span: Span::new(0, 9),
+        })
+    );
+}
+
+#[test]
+fn test_const_references() {
+    assert_eq!(
+        run("const A = 1; const B = A + 1; B"),
+        Some(Typing::Int64(2))
+    );
+    assert_eq!(
+        run("const A = 2; { const B = -A; B }"),
+        Some(Typing::Int64(-2))
+    );
+    assert_eq!(
+        run("const A = 2; fn f() -> int { const B = A * 2; B } f()"),
+        Some(Typing::Int64(4))
+    );
+
+    let non_constant = |contents| match parse_err(contents) {
+        ParseError::ConstNotConstant { id, pos, .. } => (id, pos.col()),
+        err => panic!("Expected ConstNotConstant, got {err:?}"),
+    };
+    assert_eq!(
+        non_constant("let A = 1; const B = A"),
+        ("B".to_string(), 22)
+    );
+    assert_eq!(
+        non_constant("const A = 1; { let A = 2; const B = A }"),
+        ("B".to_string(), 37)
+    );
+    assert_eq!(
+        non_constant("const A = 1; fn f(A: int) { const B = A; }"),
+        ("B".to_string(), 39)
+    );
+    assert_eq!(
+        non_constant("const A = 1; for A in 0..<1 { const B = A; }"),
+        ("B".to_string(), 41)
+    );
+    assert_eq!(
+        non_constant("{ const A = 1; } const B = A"),
+        ("B".to_string(), 28)
+    );
+    assert_eq!(
+        non_constant("fn f() { const A = 1; fn g() { const B = A; } }"),
+        ("B".to_string(), 42)
+    );
+}
+
+#[test]
+fn test_assignment() {
+    let (val, env) = run_with(run_env("var x = 1"), "x = 5").unwrap();
+    assert_eq!(val, Some(Typing::Int64(5)));
+    assert_eq!(env.get(&"x".to_string()), Some(&Typing::Int64(5)));
+
+    let mut env = run_env("var x = 1");
+    env.declare("y".to_string(), DeclarationKind::Var, Typing::Int64(0));
+    let (val, env) = run_with(env, "x = y = 7").unwrap();
+    assert_eq!(val, Some(Typing::Int64(7)));
+    assert_eq!(env.get(&"x".to_string()), Some(&Typing::Int64(7)));
+    assert_eq!(env.get(&"y".to_string()), Some(&Typing::Int64(7)));
+
+    assert_eq!(
+        run_with(run_env("var x = 1"), "x = true").unwrap_err(),
+        RuntimeError::VariableTypeMismatch {
+            id: "x".to_string(),
+            expected: RawTyping::Int64,
+            found: RawTyping::Boolean,
+            pos: Pos::new(1, 1),
+            span: Span::new(0, 8),
+        }
+    );
+
+    for (contents, kind) in [
+        ("let x = 1", DeclarationKind::Let),
+        ("const x = 1", DeclarationKind::Const),
+    ] {
+        assert_eq!(
+            run_with(run_env(contents), "x = 5").unwrap_err(),
+            RuntimeError::AssignImmutable {
+                id: "x".to_string(),
+                kind,
+                pos: Pos::new(1, 1),
+                span: Span::new(0, 5),
+            }
+        );
+    }
+
+    assert_eq!(
+        run_with(Environment::new(), "  x = 5").unwrap_err(),
+        RuntimeError::UndefinedVariable {
+            id: "x".to_string(),
+            pos: Pos::new(1, 3),
+            span: Span::new(2, 7),
+            suggestions: Vec::new()
+        }
+    );
+    assert_eq!(
+        parse_err("1 + 2 = 3"),
+        ParseError::InvalidAssignTarget {
+            found: Token::Assign,
+            pos: Pos::new(1, 7),
+            span: Span::new(6, 7),
+        }
+    );
+    assert_eq!(
+        parse_err("(x) += 3"),
+        ParseError::InvalidAssignTarget {
+            found: Token::AssignMathAdd,
+            pos: Pos::new(1, 5),
+            span: Span::new(4, 6),
+        }
+    );
+}
+
+#[test]
+fn test_environment_set() {
+    let x = "x".to_string();
+    let mut env = Environment::new();
+    assert_eq!(env.set(&x, Typing::Int64(1)), Err(SetError::Undefined));
+
+    // set writes to the outer variable instead of declaring a new one.
+    env.declare(x.clone(), DeclarationKind::Var, Typing::Int64(1));
+    env.push();
+    assert_eq!(env.set(&x, Typing::Int64(2)), Ok(Typing::Int64(1)));
+    assert!(!env.contains_offset(&x, 0));
+    env.pop();
+    assert_eq!(env.get(&x), Some(&Typing::Int64(2)));
+    assert_eq!(
+        env.set(&x, Typing::Boolean(true)),
+        Err(SetError::TypeMismatch {
+            expected: RawTyping::Int64,
+            found: RawTyping::Boolean
+        })
+    );
+
+    env.declare(x.clone(), DeclarationKind::Let, Typing::Int64(3));
+    env.push();
+    assert_eq!(
+        env.set(&x, Typing::Int64(4)),
+        Err(SetError::Immutable(DeclarationKind::Let))
+    );
+    assert_eq!(env.get(&x), Some(&Typing::Int64(3)));
+}
+
+#[test]
+fn test_compound_assignment() {
+    for (contents, expected) in [
+        ("x *= 3", Typing::Int64(30)),
+        ("x /= 3", Typing::Int64(3)),
+        ("x %= 3", Typing::Int64(1)),
+        ("x += 3", Typing::Int64(13)),
+        ("x -= 3", Typing::Int64(7)),
+        ("x <<= 2", Typing::Int64(40)),
+        ("x >>= 2", Typing::Int64(2)),
+        ("x &= 6", Typing::Int64(2)),
+        ("x ^= 6", Typing::Int64(12)),
+        ("x |= 6", Typing::Int64(14)),
+        ("x += 2 * 3", Typing::Int64(16)),
+        ("x -= x", Typing::Int64(0)),
+        ("x *= x + 1", Typing::Int64(110)),
+    ] {
+        let (val, env) = run_with(run_env("var x = 10"), contents).unwrap();
+        assert_eq!(val.as_ref(), Some(&expected), "{contents}");
+        assert_eq!(env.get(&"x".to_string()), Some(&expected), "{contents}");
+    }
+
+    let mut env = run_env("var x = 10");
+    env.declare("y".to_string(), DeclarationKind::Var, Typing::Int64(1));
+    let (_, env) = run_with(env, "x += y += 1").unwrap();
+    assert_eq!(env.get(&"x".to_string()), Some(&Typing::Int64(12)));
+    assert_eq!(env.get(&"y".to_string()), Some(&Typing::Int64(2)));
+
+    assert_eq!(
+        run_with(run_env("var x = 1"), "x += 0.5").unwrap_err(),
+        RuntimeError::VariableTypeMismatch {
+            id: "x".to_string(),
+            expected: RawTyping::Int64,
+            found: RawTyping::Float64,
+            pos: Pos::new(1, 1),
+            span: Span::new(0, 8),
+        }
+    );
+    assert_eq!(
+        run_with(run_env("var x = 255u8"), "x += 1u8").unwrap_err(),
+        RuntimeError::Overflow {
+            ty: RawTyping::UInt8,
+            pos: Pos::new(1, 3),
+            span: Span::new(0, 8),
+        }
+    );
+    assert_eq!(
+        run_with(run_env("let x = 1"), "x += 1").unwrap_err(),
+        RuntimeError::AssignImmutable {
+            id: "x".to_string(),
+            kind: DeclarationKind::Let,
+            pos: Pos::new(1, 1),
+            span: Span::new(0, 6),
+        }
+    );
+
+    // `~=` is not an operator, it is `~` then `=`.
+    let tokens: Vec<Token> = lex("x ~= 1")
+        .unwrap()
+        .into_iter()
+        .map(|t| t.token)
+        .collect();
+    assert_eq!(tokens[1..3], [Token::BitNot, Token::Assign]);
+}
+
+#[test]
+fn test_lookup() {
+    let mut env = run_env("let count: u8 = 3u8");
+    env.declare(
+        "name".to_string(),
+        DeclarationKind::Var,
+        Typing::Character('k'),
+    );
+    assert_eq!(
+        run_with(env, "count * 2u8 + count").unwrap().0,
+        Some(Typing::UInt8(9))
+    );
+
+    let (val, _) = run_with(run_env("var x = 1"), "(x = 4) + x").unwrap();
+    assert_eq!(val, Some(Typing::Int64(8)));
+    assert_eq!(
+        run_err("-missing").to_string(),
+        "Runtime: Undefined variable 'missing' at (1:2)."
+    );
+}
+
+#[test]
+fn test_undefined_suggestions() {
+    let undefined = |contents: &str| {
+        let mut env = Environment::new();
+        for name in ["count", "counter", "amount", "name", "x"] {
+            env.declare(name.to_string(), DeclarationKind::Var, Typing::Int64(1));
+        }
+        match run_with(env, contents).unwrap_err() {
+            RuntimeError::UndefinedVariable { suggestions, .. } => suggestions,
+            err => panic!("Expected UndefinedVariable, got {err:?}"),
+        }
+    };
+
+    assert_eq!(undefined("conut"), ["count"]);
+    assert_eq!(undefined("countr"), ["count", "counter"]);
+    assert_eq!(undefined("nme + 1"), ["name"]);
+    assert_eq!(undefined("y"), ["x"]);
+    assert_eq!(undefined("zzz"), Vec::<String>::new());
+    assert_eq!(undefined("mount = 1"), ["amount", "count"]);
+    assert_eq!(undefined("\"{nam}\""), ["name"]);
+
+    let mut env = Environment::new();
+    env.declare("count".to_string(), DeclarationKind::Var, Typing::Int64(1));
+    env.declare(
+        "counter".to_string(),
+        DeclarationKind::Var,
+        Typing::Int64(1),
+    );
+    assert_eq!(
+        run_with(env, "1 + countr").unwrap_err().to_string(),
+        "Runtime: Undefined variable 'countr' at (1:5), did you mean one of 'count', 'counter'?"
+    );
+
+    let mut env = Environment::new();
+    env.declare("count".to_string(), DeclarationKind::Var, Typing::Int64(1));
+    env.push();
+    env.declare("limit".to_string(), DeclarationKind::Var, Typing::Int64(1));
+    assert_eq!(
+        run_with(env, "conut + limt").unwrap_err().to_string(),
+        "Runtime: Undefined variable 'conut' at (1:1), did you mean 'count'?"
+    );
+}